@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -6,18 +8,8 @@ pub struct Root {
     pub polkadot: Polkadot,
 }
 
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct LDORoot {
-    #[serde(alias = "lido-dao")]
-    pub lido_dao: Lido,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct ASRoot {
-    pub arthswap: Arthswap,
-}
+/// `simple/price` for any ids, keyed by coin id.
+pub type SimplePriceRoot = HashMap<String, UsdPrice>;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,12 +19,6 @@ pub struct Polkadot {
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct Arthswap {
-    pub usd: f64,
-}
-
-#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Lido {
+pub struct UsdPrice {
     pub usd: f64,
 }
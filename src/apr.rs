@@ -0,0 +1,217 @@
+use serde::Deserialize;
+
+use crate::config;
+use crate::models;
+use crate::store;
+
+/// How the daily volumes in a window are averaged.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "averaging")]
+pub enum Averaging {
+    /// Every day counts the same.
+    Simple,
+    /// The most recent day weighs `days`, the oldest 1.
+    Linear,
+    /// A day's weight halves every `half_life` days.
+    Exponential { half_life: f64 },
+}
+
+/// The last `days` days of volume, averaged with `averaging`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Window {
+    pub days: usize,
+    #[serde(flatten)]
+    pub averaging: Averaging,
+}
+
+/// A week of volume, each day weighing the same.
+pub const DEFAULT_WINDOW: Window = Window {
+    days: 7,
+    averaging: Averaging::Simple,
+};
+
+impl Default for Window {
+    fn default() -> Self {
+        DEFAULT_WINDOW
+    }
+}
+
+impl Window {
+    fn weight(&self, age: usize) -> f64 {
+        match self.averaging {
+            Averaging::Simple => 1.0,
+            Averaging::Linear => (self.days - age) as f64,
+            Averaging::Exponential { half_life } => 0.5_f64.powf(age as f64 / half_life),
+        }
+    }
+
+    /// Weighted average of `daily_volumes`, most recent day first. Days past the window
+    /// are ignored.
+    pub fn average(&self, daily_volumes: &[f64]) -> f64 {
+        let mut volume = 0.0;
+        let mut weights = 0.0;
+        for (age, dv) in daily_volumes.iter().take(self.days).enumerate() {
+            let weight = self.weight(age);
+            volume += dv * weight;
+            weights += weight;
+        }
+        if weights == 0.0 {
+            return 0.0;
+        }
+        volume / weights
+    }
+}
+
+/// Trading fee APR in percent of a pool with `liquidity` usd in it, from its usd volume
+/// per day (most recent first) and the share of the volume paid to LPs.
+pub fn base_apr(daily_volumes: &[f64], liquidity: f64, lp_fee: f64, window: Window) -> f64 {
+    if liquidity == 0.0 {
+        return 0.0;
+    }
+    let apr = window.average(daily_volumes) * lp_fee * 365.0 * 100.0 / liquidity;
+    if apr.is_finite() {
+        apr
+    } else {
+        0.0
+    }
+}
+
+/// APY in percent of `apr` percent compounded `periods` times a year.
+pub fn apy(apr: f64, periods: f64) -> f64 {
+    ((1.0 + apr / 100.0 / periods).powf(periods) - 1.0) * 100.0
+}
+
+/// Total APR, APY and net APR of a farm paying `apr` on the `terms`.
+pub fn yields(
+    apr: &models::APR,
+    terms: &models::FarmTerms,
+    config: &config::Yields,
+) -> models::Yields {
+    let total_apr = apr.base + apr.reward;
+    let (compounded, uncompounded) = match terms.vesting {
+        Some(_) => (apr.base, apr.reward),
+        None => (total_apr, 0.0),
+    };
+    let fees = (terms.deposit_fee + terms.withdrawal_fee) * 100.0;
+
+    models::Yields {
+        total_apr,
+        apy: apy(compounded, config.compounding.periods_per_year()) + uncompounded,
+        compounding: config.compounding,
+        net_apr: total_apr - fees * 365.0 / config.holding_days as f64,
+        holding_days: config.holding_days,
+    }
+}
+
+/// Writes the `yields` of every farm from the APRs and terms the other jobs stored.
+pub async fn farm_yields(
+    store: &dyn store::Store,
+    config: &config::Yields,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut farms = store.farms().await?;
+    for farm in farms.iter_mut() {
+        farm.yields = Some(yields(&farm.apr, &farm.terms, config));
+        store.upsert_farm(farm).await?;
+    }
+    log::info!("yields written for {} farms", farms.len());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn window_average() {
+        let volumes = [30.0, 20.0, 10.0, 999.0];
+        for (averaging, daily_volumes, expected) in [
+            (Averaging::Simple, &volumes[..], 20.0),
+            (Averaging::Linear, &volumes[..], 140.0 / 6.0),
+            (
+                Averaging::Exponential { half_life: 1.0 },
+                &volumes[..],
+                42.5 / 1.75,
+            ),
+            (Averaging::Linear, &volumes[..1], 30.0),
+            (Averaging::Simple, &[][..], 0.0),
+        ] {
+            let window = Window { days: 3, averaging };
+            assert_close(window.average(daily_volumes), expected);
+        }
+    }
+
+    #[test]
+    fn base_apr_of_window() {
+        for (liquidity, expected) in [(36500.0, 0.3), (0.0, 0.0)] {
+            assert_close(
+                base_apr(&[100.0; 7], liquidity, 0.003, DEFAULT_WINDOW),
+                expected,
+            );
+        }
+    }
+
+    #[test]
+    fn apy_of_apr() {
+        for (apr, periods, expected) in [
+            (0.0, 365.0, 0.0),
+            (100.0, 1.0, 100.0),
+            (12.0, 12.0, 12.682503013196978),
+            (10.0, 52.0, 10.50647927797661),
+        ] {
+            assert_close(apy(apr, periods), expected);
+        }
+    }
+
+    #[test]
+    fn net_apr_amortises_fees() {
+        let apr = models::APR {
+            base: 10.0,
+            reward: 20.0,
+        };
+        let terms = models::FarmTerms {
+            deposit_fee: 0.01,
+            withdrawal_fee: 0.005,
+            ..Default::default()
+        };
+        for (holding_days, expected) in [(365, 28.5), (30, 11.75), (730, 29.25)] {
+            let config = config::Yields {
+                holding_days,
+                ..Default::default()
+            };
+            let yields = yields(&apr, &terms, &config);
+            assert_close(yields.total_apr, 30.0);
+            assert_close(yields.net_apr, expected);
+        }
+    }
+
+    #[test]
+    fn vested_rewards_are_not_compounded() {
+        let apr = models::APR {
+            base: 10.0,
+            reward: 20.0,
+        };
+        let config = config::Yields::default();
+        let vested = models::FarmTerms {
+            vesting: Some(0),
+            ..Default::default()
+        };
+
+        let paid_out = yields(&apr, &models::FarmTerms::default(), &config);
+        let vesting = yields(&apr, &vested, &config);
+
+        assert_eq!(paid_out.apy, apy(30.0, 365.0));
+        assert_eq!(vesting.apy, apy(10.0, 365.0) + 20.0);
+        assert!(vesting.apy < paid_out.apy);
+        assert_eq!(vesting.total_apr, paid_out.total_apr);
+    }
+}
@@ -0,0 +1,147 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::prelude::Utc;
+use ethers::providers::Middleware;
+use gql_client::Client;
+use serde::Serialize;
+
+use crate::chef::ChainClient;
+use crate::config;
+use crate::constants;
+use crate::subgraph;
+
+/// Seconds in a day.
+pub const DAY: i64 = 60 * 60 * 24;
+
+/// Seconds after the timestamp the blocks subgraph is searched in.
+const SUBGRAPH_WINDOW: i64 = 600;
+
+/// Finds the block a chain was at at some time, from the chain's blocks subgraph when it
+/// has one and by binary searching its blocks over rpc otherwise.
+///
+/// Blocks found are kept for the run, so every N-days-ago lookup on a chain in one run
+/// gets the same block.
+pub struct BlockFinder {
+    subgraphs: HashMap<String, String>,
+    clients: HashMap<String, ChainClient>,
+    found: Mutex<HashMap<(String, i64), u64>>,
+}
+
+impl BlockFinder {
+    pub fn new(chains: &[config::Chain], clients: HashMap<String, ChainClient>) -> Self {
+        BlockFinder {
+            subgraphs: chains
+                .iter()
+                .filter_map(|c| Some((c.name.clone(), c.blocks_subgraph.clone()?)))
+                .collect(),
+            clients,
+            found: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The first block of `chain` at or after `timestamp` (unix seconds).
+    pub async fn block_at_timestamp(
+        &self,
+        chain: &str,
+        timestamp: i64,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let key = (chain.to_string(), timestamp);
+        if let Some(block) = self.found.lock().unwrap().get(&key) {
+            return Ok(*block);
+        }
+
+        let mut block = None;
+        if let Some(url) = self.subgraphs.get(chain) {
+            match subgraph_block(url, timestamp).await {
+                Ok(Some(number)) => block = Some(number),
+                Ok(None) => log::debug!("{} blocks subgraph has no block at {}", chain, timestamp),
+                Err(e) => log::warn!("{} blocks subgraph failed: {}", chain, e),
+            }
+        }
+        let block = match block {
+            Some(block) => block,
+            None => self.rpc_block(chain, timestamp).await?,
+        };
+        log::debug!("{} block at {} is {}", chain, timestamp, block);
+
+        self.found.lock().unwrap().insert(key, block);
+        Ok(block)
+    }
+
+    /// The block `chain` was at `days` days ago.
+    pub async fn block_days_ago(
+        &self,
+        chain: &str,
+        days: u32,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        // to the minute, so the lookups of one run share their blocks
+        let now = Utc::now().timestamp() / 60 * 60;
+        self.block_at_timestamp(chain, now - days as i64 * DAY)
+            .await
+    }
+
+    /// Binary searches for the first block at or after `timestamp`.
+    async fn rpc_block(
+        &self,
+        chain: &str,
+        timestamp: i64,
+    ) -> Result<u64, Box<dyn std::error::Error>> {
+        let client = self
+            .clients
+            .get(chain)
+            .ok_or_else(|| format!("no rpc client for {}", chain))?;
+
+        let latest = client.get_block_number().await?.as_u64();
+        if block_timestamp(client, latest).await? < timestamp {
+            return Err(format!("{} hasn't reached {} yet", chain, timestamp).into());
+        }
+
+        let (mut low, mut high) = (0, latest);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if block_timestamp(client, mid).await? < timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+}
+
+async fn block_timestamp(
+    client: &ChainClient,
+    number: u64,
+) -> Result<i64, Box<dyn std::error::Error>> {
+    let block = client
+        .get_block(number)
+        .await?
+        .ok_or_else(|| format!("block {} not found", number))?;
+    Ok(block.timestamp.as_u64() as i64)
+}
+
+async fn subgraph_block(url: &str, timestamp: i64) -> Result<Option<u64>, String> {
+    #[derive(Serialize)]
+    pub struct Vars {
+        start: i64,
+        end: i64,
+    }
+    let vars = Vars {
+        start: timestamp,
+        end: timestamp + SUBGRAPH_WINDOW,
+    };
+
+    let client = Client::new(url.to_string(), 60);
+    let blocks_data = client
+        .query_with_vars_unwrap::<subgraph::BlocksData, Vars>(constants::chef::BLOCKS_QUERY, vars)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    match blocks_data.blocks.get(0) {
+        Some(block) => {
+            Ok(Some(block.number.parse().map_err(|_| {
+                format!("bad block number {}", block.number)
+            })?))
+        }
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,273 @@
+use std::sync::Arc;
+
+use chrono::prelude::Utc;
+use ethers::prelude::{Address, U256};
+use futures::future::LocalBoxFuture;
+
+use crate::chef::{Chef, Context, FarmSource};
+use crate::constants;
+use crate::contracts;
+use crate::models;
+use crate::multicall;
+use crate::store;
+
+/// Arthswap's v4 chef on Astar, priced through the Arthswap assets from the dexscreener job.
+pub struct Arthswap {
+    pub chef: Chef,
+}
+
+impl FarmSource for Arthswap {
+    fn chef(&self) -> &Chef {
+        &self.chef
+    }
+
+    fn prefetch<'a>(
+        &'a self,
+        pids: &'a [u32],
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+            let arthswap_chef = contracts::IArthswapChef::new(
+                chef.address.parse::<Address>()?,
+                Arc::clone(&chef.client),
+            );
+            let mut calls = vec![];
+            for pid in pids {
+                calls.extend(multicall::key(&arthswap_chef.pool_infos(U256::from(*pid))));
+                calls.extend(multicall::key(&arthswap_chef.lp_tokens(U256::from(*pid))));
+            }
+            chef.calls.prefetch(calls).await?;
+
+            let mut calls = vec![];
+            for pid in pids {
+                if let Some(lp_token) = chef
+                    .calls
+                    .cached(&arthswap_chef.lp_tokens(U256::from(*pid)))
+                {
+                    let lp = contracts::IStandardLpToken::new(lp_token, Arc::clone(&chef.client));
+                    calls.extend(multicall::key(&lp.token_0()));
+                    calls.extend(multicall::key(&lp.token_1()));
+                }
+            }
+            chef.calls.prefetch(calls).await
+        })
+    }
+
+    fn fetch_farm<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+            let router = chef.router.clone();
+
+            if pid != 31 && pid != 37 && pid < 44 {
+                let arthswap_chef_address = chef.address.parse::<Address>()?;
+                let arthswap_chef =
+                    contracts::IArthswapChef::new(arthswap_chef_address, Arc::clone(&chef.client));
+
+                let (acc_arsw_per_share, last_reward_block, alloc_point): (u128, u64, u64) = chef
+                    .calls
+                    .call(arthswap_chef.pool_infos(ethers::prelude::U256::from(pid)))
+                    .await?;
+
+                log::debug!(
+                    "acc_arsw_per_share {:?} last_reward_block {:?} alloc_point {:?}",
+                    acc_arsw_per_share,
+                    last_reward_block,
+                    alloc_point
+                );
+
+                let lp_tokens = chef
+                    .calls
+                    .call(arthswap_chef.lp_tokens(ethers::prelude::U256::from(pid)))
+                    .await?;
+
+                log::debug!("lp_tokens {:?}", lp_tokens);
+
+                let asset_addr = ethers::utils::to_checksum(&lp_tokens.to_owned(), None);
+                log::debug!("asset_addr {:?}", asset_addr.clone());
+                let asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    &asset_addr,
+                    &chef.chain,
+                    &chef.protocol,
+                )
+                .await?;
+
+                let ap = alloc_point;
+
+                log::debug!("asset {:?} alloc_point {:?}", asset, ap);
+
+                let farm_type = models::FarmType::StandardAmm;
+                let farm_implementation = chef.farm_implementation;
+
+                let arthswap_lp_address = asset_addr.parse::<Address>()?;
+                let arthswap_lp =
+                    contracts::IStandardLpToken::new(arthswap_lp_address, Arc::clone(&chef.client));
+                let token0: Address = chef.calls.call(arthswap_lp.token_0()).await?;
+                let token1: Address = chef.calls.call(arthswap_lp.token_1()).await?;
+                log::debug!("token 0 {:?} 1 {:?}", token0, token1);
+
+                // decimals come from the token contracts, the symbol the pair was
+                // indexed under (e.g. ceUSDT) is kept when the asset has one.
+                let mut underlying_assets = vec![];
+                let mut logos = vec![];
+                for token in [token0, token1] {
+                    let address = ethers::utils::to_checksum(&token, None);
+                    let metadata = ctx.tokens.resolve(&chef.chain, &address).await?;
+                    let symbol = asset
+                        .clone()
+                        .underlying_assets
+                        .iter()
+                        .find(|ua| ua.address == address)
+                        .map_or(metadata.symbol, |ua| ua.symbol.clone());
+                    logos.push(format!(
+                        "https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png",
+                        symbol
+                    ));
+                    underlying_assets.push(models::UnderlyingAsset {
+                        symbol,
+                        address,
+                        decimals: metadata.decimals,
+                    });
+                }
+                // for ua in asset.clone().underlying_assets {
+                //     let uaa = ctx.store.find_asset(&asset_addr, &chef.chain, &chef.protocol).await?;
+                //     let mut def_decimals = uaa.unwrap_or_default().decimals;
+                //     log::debug!("def_decimals {:?} {:?}", ua.symbol.clone(), def_decimals);
+                //     if def_decimals == 0 {
+                //         def_decimals = ua.decimals;
+                //     }
+                //     underlying_assets.push(bson!({
+                //         "symbol": ua.symbol,
+                //         "address": ua.address,
+                //         "decimals": def_decimals,
+                //     }))
+                // }
+                log::debug!("arthswap uas {:?}", underlying_assets.clone());
+                // let mut logos = vec![];
+                // for ua in underlying_assets.clone() {
+                //     logos
+                //         .push(format!(
+                //         "https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png",
+                //         ua));
+                // }
+                let mut rewards: Vec<models::Reward> = vec![];
+                let mut total_reward_apr = 0.0;
+                let arsw = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::arthswap_on_astar::ARSW,
+                    &chef.chain,
+                    &chef.protocol,
+                )
+                .await?;
+                let arsw_price = arsw.clone().price;
+                let asset_price = asset.clone().price;
+                let asset_tvl = asset.clone().liquidity;
+
+                log::debug!("arsw {:?} asset {:?}", arsw.clone(), asset.clone());
+
+                if ap > 0 {
+                    let block_time = chef.block_time;
+
+                    let tap: U256 = chef.calls.call(arthswap_chef.total_alloc_point()).await?;
+
+                    // the period of the block the run's reads are pinned to
+                    let block_number = match chef.block {
+                        Some(block) => block,
+                        None => {
+                            ethers_providers::Middleware::get_block_number(&chef.client.clone())
+                                .await?
+                                .as_u64()
+                        }
+                    };
+                    log::debug!("block_number {:?}", block_number);
+                    // get period (call arthswap_chef.get_period)
+                    let period: U256 = chef
+                        .calls
+                        .call(arthswap_chef.get_period(ethers::prelude::U256::from(block_number)))
+                        .await?;
+                    log::debug!("period {:?}", period);
+                    let arsw_per_block: U256 = chef
+                        .calls
+                        .call(
+                            arthswap_chef
+                                .arsw_per_block(ethers::prelude::U256::from(period.as_u64())),
+                        )
+                        .await?;
+                    let arsw_per_sec = arsw_per_block.as_u128() as f64 / block_time;
+
+                    let rewards_per_sec: f64 = arsw_per_sec * (ap as f64 / tap.as_u128() as f64);
+                    let rewards_per_day: f64 = rewards_per_sec * 60.0 * 60.0 * 24.0;
+
+                    if rewards_per_day != 0.0 {
+                        rewards.push(models::Reward {
+                            amount: rewards_per_day as f64
+                                / constants::utils::TEN_I128.pow(arsw.clone().decimals) as f64,
+                            asset: arsw.clone().symbol,
+                            value_usd: (rewards_per_day as f64
+                                / constants::utils::TEN_I128.pow(arsw.clone().decimals) as f64)
+                                * arsw_price,
+                            freq: models::Freq::Daily.to_string(),
+                        });
+
+                        // reward_apr/farm_apr/pool_apr
+                        log::debug!(
+                            "rewards/sec: {} rewards/day: {} asset_tvl: {}",
+                            rewards_per_sec,
+                            rewards_per_day,
+                            asset_tvl
+                        );
+
+                        let reward_apr = ((rewards_per_day as f64 * arsw_price)
+                            / (asset_tvl as f64
+                                * constants::utils::TEN_I128.pow(arsw.clone().decimals) as f64))
+                            * 365.0
+                            * 100.0;
+                        log::debug!("reward_apr: {}", reward_apr);
+                        if asset_tvl != 0.0 && asset_price != 0.0 {
+                            total_reward_apr += reward_apr;
+                        }
+                    }
+                }
+
+                let timestamp = Utc::now().to_string();
+
+                log::debug!("chef v4 farm lastUpdatedAtUTC {}", timestamp.clone());
+
+                let farm = models::Farm {
+                    id: pid as i32,
+                    chef: chef.address.clone(),
+                    router,
+                    chain: chef.chain.clone(),
+                    protocol: chef.protocol.clone(),
+                    farm_type: farm_type.to_string(),
+                    farm_impl: farm_implementation.to_string(),
+                    asset: models::FarmAsset {
+                        symbol: asset.clone().symbol,
+                        address: asset_addr.clone(),
+                        price: asset.clone().price,
+                        logos,
+                        underlying_assets,
+                    },
+                    tvl: asset_tvl,
+                    apr: models::APR {
+                        reward: total_reward_apr,
+                        base: asset.clone().fees_apr,
+                    },
+                    rewards,
+                    alloc_point: ap as u32,
+                    last_updated_at_utc: timestamp.clone(),
+                    block: chef.block,
+                    terms: chef.terms(),
+                    ..Default::default()
+                };
+                ctx.store.upsert_farm(&farm).await?;
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,271 @@
+use std::sync::Arc;
+
+use chrono::prelude::Utc;
+use ethers::prelude::{Address, U256};
+use futures::future::LocalBoxFuture;
+
+use crate::apis;
+use crate::apr;
+use crate::chef::chef_v2::{self, ChefV2, Pool};
+use crate::chef::{Chef, Context, FarmSource};
+use crate::constants;
+use crate::contracts;
+use crate::lp;
+use crate::models;
+use crate::store;
+
+/// RUM-WGLMR, which the Beamswap subgraph doesn't price; dexscreener is used instead.
+const RUM_PID: u32 = 24;
+const DEXSCREENER_PAIRS_RUM_URL: &str =
+    "https://api.dexscreener.com/latest/dex/pairs/moonbeam/0x8A2982bA47Aa7a3A072E62930BEe8649B53a3dfe";
+
+pub struct Beamswap {
+    pub chef: Chef,
+}
+
+impl FarmSource for Beamswap {
+    fn chef(&self) -> &Chef {
+        &self.chef
+    }
+
+    fn fetch_assets<'a>(
+        &'a self,
+        ctx: &'a Context,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::upsert_poop_assets(
+            ctx,
+            &self.chef,
+            constants::addresses::beamswap_on_moonbeam::WGLMR,
+            constants::addresses::beamswap_on_moonbeam::POOP,
+            constants::addresses::beamswap_on_moonbeam::WGLMR_POOP_LP,
+        ))
+    }
+
+    fn prefetch<'a>(
+        &'a self,
+        pids: &'a [u32],
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::prefetch(&self.chef, pids))
+    }
+
+    fn fetch_farm<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::fetch_farm(self, ctx, pid))
+    }
+}
+
+impl ChefV2 for Beamswap {
+    fn pool<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+        lp_token: Address,
+    ) -> LocalBoxFuture<'a, Result<Pool, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+            let mut pool = Pool::standard(chef);
+
+            // 4pool
+            if pid == 16 {
+                pool.farm_type = models::FarmType::StableAmm;
+
+                let stable_asset =
+                    contracts::IStableLpToken::new(lp_token, Arc::clone(&chef.client));
+                let symbol: String = chef.calls.call(stable_asset.symbol()).await?;
+
+                let owner_addr: Address = chef.calls.call(stable_asset.owner()).await?;
+                pool.stable_owner_addr = ethers::utils::to_checksum(&owner_addr.to_owned(), None);
+                pool.router = pool.stable_owner_addr.clone();
+
+                let owner =
+                    contracts::IStableLpTokenOwner::new(owner_addr, Arc::clone(&chef.client));
+                let stable_lp_underlying_tokens: Vec<Address> =
+                    chef.calls.call(owner.get_tokens()).await?;
+                let stable_lp_underlying_balances =
+                    chef.calls.call(owner.get_token_balances()).await?;
+                log::debug!(
+                    "stable_lp_underlying_tokens: {:#?}",
+                    stable_lp_underlying_tokens
+                );
+                log::debug!(
+                    "stable_lp_underlying_balances: {:#?}",
+                    stable_lp_underlying_balances
+                );
+
+                let stable_lp = lp::stable_lp_price(
+                    &chef.calls,
+                    ctx.store.as_ref(),
+                    &chef.chain,
+                    &chef.protocol,
+                    lp_token,
+                )
+                .await?;
+                log::debug!("{} stable_lp {:?}", symbol, stable_lp);
+
+                let busd_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::beamswap_on_moonbeam::BUSD,
+                    "moonbeam",
+                    "beamswap",
+                )
+                .await?;
+                let usdc_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::beamswap_on_moonbeam::USDC,
+                    "moonbeam",
+                    "beamswap",
+                )
+                .await?;
+                let usdt_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::beamswap_on_moonbeam::USDT,
+                    "moonbeam",
+                    "beamswap",
+                )
+                .await?;
+                let dai_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::beamswap_on_moonbeam::DAI,
+                    "moonbeam",
+                    "beamswap",
+                )
+                .await?;
+
+                if symbol == "4pool".to_string() {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: busd_asset.clone().symbol,
+                            address: busd_asset.clone().address,
+                            decimals: busd_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdc_asset.clone().symbol,
+                            address: usdc_asset.clone().address,
+                            decimals: usdc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdt_asset.clone().symbol,
+                            address: usdt_asset.clone().address,
+                            decimals: usdt_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: dai_asset.clone().symbol,
+                            address: dai_asset.clone().address,
+                            decimals: dai_asset.clone().decimals,
+                        },
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::beamswap_on_moonbeam::_4POOL.to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "Beamswap Stable DEX - Stable Multichain".to_string(),
+                        symbol: "4pool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            dai_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                }
+            }
+
+            if pid == 5 {
+                pool.farm_type = models::FarmType::SingleStaking;
+            }
+
+            Ok(pool)
+        })
+    }
+
+    fn asset_price_and_tvl<'a>(
+        &'a self,
+        _ctx: &'a Context,
+        pid: u32,
+        asset: &'a models::Asset,
+        total_lp: U256,
+    ) -> LocalBoxFuture<'a, Result<(f64, u128), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            if pid != RUM_PID {
+                return Ok((asset.price, total_lp.as_u128()));
+            }
+
+            let glmb_d2o_pairs = reqwest::get(DEXSCREENER_PAIRS_RUM_URL)
+                .await?
+                .json::<apis::dx2::Root>()
+                .await?;
+
+            let asset_price: f64 = glmb_d2o_pairs
+                .pair
+                .price_usd
+                .clone()
+                .parse()
+                .unwrap_or_default();
+            let liq: u128 = (glmb_d2o_pairs.pair.liquidity.usd / asset_price) as u128;
+            let asset_tvl = liq * constants::utils::TEN_F64.powf(18.0) as u128;
+            log::debug!(
+                "meow asset_price {:?} asset_tvl {:?}",
+                asset_price,
+                asset_tvl
+            );
+
+            Ok((asset_price, asset_tvl))
+        })
+    }
+
+    fn base_apr<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+        asset: &'a models::Asset,
+        _pool: &'a Pool,
+        asset_price: f64,
+    ) -> LocalBoxFuture<'a, Result<f64, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let mut base_apr = chef_v2::pair_day_datas_base_apr(ctx, &self.chef, asset).await?;
+
+            if pid == RUM_PID && (asset.total_supply == 0.0 || asset.price == 0.0) {
+                log::debug!("meowbaseapr");
+                let glmb_d2o_pairs = reqwest::get(DEXSCREENER_PAIRS_RUM_URL)
+                    .await?
+                    .json::<apis::dx2::Root>()
+                    .await?;
+
+                base_apr = apr::base_apr(
+                    &[glmb_d2o_pairs.pair.volume.h24],
+                    asset.total_supply * asset_price,
+                    self.chef.lp_fee,
+                    ctx.base_apr,
+                );
+                log::debug!("meowbase_apr {:?}", base_apr);
+            }
+
+            Ok(base_apr)
+        })
+    }
+}
@@ -0,0 +1,673 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::prelude::Utc;
+use ethers::prelude::{Address, U256};
+use futures::future::LocalBoxFuture;
+use serde::Serialize;
+
+use crate::apr;
+use crate::chef::{Chef, Context, FarmSource};
+use crate::constants;
+use crate::contracts;
+use crate::lp;
+use crate::models;
+use crate::multicall;
+use crate::store;
+use crate::subgraph;
+
+/// The subset of `IChefV2::poolInfo` the farm pipeline needs.
+pub struct PoolInfo {
+    pub lp_token: Address,
+    pub alloc_point: u32,
+    pub total_lp: U256,
+    pub terms: models::FarmTerms,
+}
+
+/// Farm type, router and underlying assets of the LP staked in a pool.
+pub struct Pool {
+    pub farm_type: models::FarmType,
+    pub router: String,
+    pub underlying_assets: Vec<models::UnderlyingAsset>,
+    pub stable_owner_addr: String,
+}
+
+impl Pool {
+    pub fn standard(chef: &Chef) -> Self {
+        Pool {
+            farm_type: models::FarmType::StandardAmm,
+            router: chef.router.clone(),
+            underlying_assets: vec![],
+            stable_owner_addr: "".to_string(),
+        }
+    }
+}
+
+/// Protocol hooks into the shared chef v2 (`poolRewardsPerSec`) farm pipeline.
+pub trait ChefV2: FarmSource {
+    /// Resolves the pool staked at `pid`, upserting stable LP assets where needed.
+    fn pool<'a>(
+        &'a self,
+        _ctx: &'a Context,
+        _pid: u32,
+        _lp_token: Address,
+    ) -> LocalBoxFuture<'a, Result<Pool, Box<dyn std::error::Error>>> {
+        Box::pin(async move { Ok(Pool::standard(self.chef())) })
+    }
+
+    /// Price of the staked asset and the raw amount staked, used for TVL and reward APR.
+    fn asset_price_and_tvl<'a>(
+        &'a self,
+        _ctx: &'a Context,
+        _pid: u32,
+        asset: &'a models::Asset,
+        total_lp: U256,
+    ) -> LocalBoxFuture<'a, Result<(f64, u128), Box<dyn std::error::Error>>> {
+        Box::pin(async move { Ok((asset.price, total_lp.as_u128())) })
+    }
+
+    /// Daily emission of `reward_asset`, given what `poolRewardsPerSec` reported.
+    fn rewards_per_day<'a>(
+        &'a self,
+        _alloc_point: u32,
+        _reward_asset: &'a models::Asset,
+        rewards_per_day: u128,
+    ) -> LocalBoxFuture<'a, Result<u128, Box<dyn std::error::Error>>> {
+        Box::pin(async move { Ok(rewards_per_day) })
+    }
+
+    fn base_apr<'a>(
+        &'a self,
+        ctx: &'a Context,
+        _pid: u32,
+        asset: &'a models::Asset,
+        _pool: &'a Pool,
+        _asset_price: f64,
+    ) -> LocalBoxFuture<'a, Result<f64, Box<dyn std::error::Error>>> {
+        Box::pin(pair_day_datas_base_apr(ctx, self.chef(), asset))
+    }
+
+    /// Pools whose farms must not be written.
+    fn skip_farm(&self, _pid: u32) -> bool {
+        false
+    }
+}
+
+pub async fn pool_info(chef: &Chef, pid: u32) -> Result<PoolInfo, Box<dyn std::error::Error>> {
+    let (
+        lp_token,
+        alloc_point,
+        last_reward_timestamp,
+        acc_native_reward_per_share,
+        deposit_fee_bp,
+        harvest_interval,
+        total_lp,
+    ): (Address, U256, U256, U256, u16, U256, U256) = chef
+        .calls
+        .call(chef.contract.pool_info(ethers::prelude::U256::from(pid)))
+        .await?;
+    log::debug!(
+        "{}, {}, {}, {}, {}, {}, {}",
+        lp_token,
+        alloc_point,
+        last_reward_timestamp,
+        acc_native_reward_per_share,
+        deposit_fee_bp,
+        harvest_interval,
+        total_lp
+    );
+
+    let ap = alloc_point.as_u32();
+
+    Ok(PoolInfo {
+        lp_token,
+        alloc_point: ap,
+        total_lp,
+        // the chefs' harvestInterval is in seconds
+        terms: models::FarmTerms {
+            deposit_fee: deposit_fee_bp as f64 / 10_000.0,
+            harvest_interval: harvest_interval.as_u64(),
+            ..chef.terms()
+        },
+    })
+}
+
+/// Prefetches what `fetch_farm` reads for `pids` in three rounds: the pools' info and
+/// rewards, then their LP tokens (as v2 pairs and as stable LPs), then the swaps behind
+/// the stable LPs.
+pub async fn prefetch(chef: &Chef, pids: &[u32]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut calls = vec![];
+    for pid in pids {
+        let pid = U256::from(*pid);
+        calls.extend(multicall::key(&chef.contract.pool_info(pid)));
+        calls.extend(multicall::key(&chef.contract.pool_rewards_per_sec(pid)));
+    }
+    chef.calls.prefetch(calls).await?;
+
+    let mut lp_tokens = vec![];
+    for pid in pids {
+        let info = chef
+            .calls
+            .cached(&chef.contract.pool_info(U256::from(*pid)));
+        if let Some((lp_token, alloc_point, _, _, _, _, _)) = info {
+            let alloc_point: U256 = alloc_point;
+            if !alloc_point.is_zero() {
+                lp_tokens.push(lp_token);
+            }
+        }
+    }
+
+    let mut calls = vec![];
+    for lp_token in lp_tokens.iter() {
+        let pair = contracts::ILpToken::new(*lp_token, Arc::clone(&chef.client));
+        calls.extend(multicall::key(&pair.token_0()));
+        calls.extend(multicall::key(&pair.token_1()));
+        calls.extend(multicall::key(&pair.get_reserves()));
+        calls.extend(multicall::key(&pair.total_supply()));
+        let stable = contracts::IStableLpToken::new(*lp_token, Arc::clone(&chef.client));
+        calls.extend(multicall::key(&stable.symbol()));
+        calls.extend(multicall::key(&stable.owner()));
+    }
+    chef.calls.prefetch(calls).await?;
+
+    let mut calls = vec![];
+    for lp_token in lp_tokens.iter() {
+        let stable = contracts::IStableLpToken::new(*lp_token, Arc::clone(&chef.client));
+        if let Some(owner) = chef.calls.cached(&stable.owner()) {
+            let owner = contracts::IStableLpTokenOwner::new(owner, Arc::clone(&chef.client));
+            calls.extend(multicall::key(&owner.get_tokens()));
+            calls.extend(multicall::key(&owner.get_token_balances()));
+            calls.extend(multicall::key(&owner.get_token_precision_multipliers()));
+            calls.extend(multicall::key(&owner.get_virtual_price()));
+        }
+    }
+    chef.calls.prefetch(calls).await
+}
+
+/// Upserts a zeroed farm for a pool that no longer receives any allocation.
+pub async fn upsert_inactive_farm(
+    ctx: &Context,
+    chef: &Chef,
+    pid: u32,
+    ap: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let farm_type = models::FarmType::StandardAmm;
+    let farm_implementation = chef.farm_implementation;
+    let router = chef.router.clone();
+
+    log::debug!("allocPoint = 0");
+
+    let timestamp = Utc::now().to_string();
+
+    log::debug!("chef v1/v2 farm lastUpdatedAtUTC {}", timestamp.clone());
+
+    let farm = models::Farm {
+        id: pid as i32,
+        chef: chef.address.clone(),
+        router,
+        chain: chef.chain.clone(),
+        protocol: chef.protocol.clone(),
+        farm_type: farm_type.to_string(),
+        farm_impl: farm_implementation.to_string(),
+        asset: models::FarmAsset {
+            symbol: "".to_string(),
+            address: "".to_string(),
+            price: 0.0,
+            logos: vec![],
+            underlying_assets: vec![],
+        },
+        tvl: 0.0,
+        apr: models::APR {
+            reward: 0.0,
+            base: 0.0,
+        },
+        rewards: vec![],
+        alloc_point: ap,
+        last_updated_at_utc: timestamp.clone(),
+        block: chef.block,
+        terms: chef.terms(),
+        ..Default::default()
+    };
+    ctx.store.upsert_farm(&farm).await?;
+
+    Ok(())
+}
+
+pub async fn fetch_farm<P: ChefV2 + ?Sized>(
+    source: &P,
+    ctx: &Context,
+    pid: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let chef = source.chef();
+    let PoolInfo {
+        lp_token,
+        alloc_point: ap,
+        total_lp,
+        terms,
+    } = pool_info(chef, pid).await?;
+
+    if ap == 0 {
+        return upsert_inactive_farm(ctx, chef, pid, ap).await;
+    }
+
+    let farm_implementation = chef.farm_implementation;
+
+    let (addresses, symbols, decimals, rewards_per_sec) = chef
+        .calls
+        .call(
+            chef.contract
+                .pool_rewards_per_sec(ethers::prelude::U256::from(pid)),
+        )
+        .await?;
+
+    log::debug!(
+                            "pool_rewards_per_sec\naddresses: {:?}, symbols: {:?}, decimals: {:?}, rewards_per_sec: {:?}",
+                            addresses, symbols, decimals, rewards_per_sec
+                        );
+
+    let pool = source.pool(ctx, pid, lp_token).await?;
+    let farm_type = pool.farm_type;
+    let router = pool.router.clone();
+    let mut underlying_assets = pool.underlying_assets.clone();
+
+    if rewards_per_sec.len() > 0 {
+        let mut total_reward_apr = 0.0;
+
+        let asset_addr = ethers::utils::to_checksum(&lp_token.to_owned(), None);
+        log::debug!("asset_addr: {:?}", asset_addr.clone());
+        let asset = ctx
+            .store
+            .find_asset(&asset_addr, &chef.chain, &chef.protocol)
+            .await?;
+        let asset = match asset {
+            Some(asset) if asset.is_lp && farm_type == models::FarmType::StandardAmm => {
+                Some(checked_lp_asset(ctx, chef, asset).await)
+            }
+            asset => asset,
+        };
+
+        let uas = asset.clone().unwrap_or_default().underlying_assets;
+        if farm_type.to_string() == "StableAmm" {
+            log::debug!("uassss {:?}", uas);
+        }
+
+        if underlying_assets.len() == 0 {
+            underlying_assets = uas;
+        }
+
+        let mut asset_price: f64 = 0.0;
+        let mut asset_tvl: u128 = 0;
+
+        let mut rewards = vec![];
+        // <symbol, (exists, amount, valueUSD, freq)>
+        let mut reward_asset_map: HashMap<String, (bool, f64, f64, String)> = HashMap::new();
+
+        if asset.is_some() {
+            for i in 0..symbols.len() {
+                log::debug!("rwrd[{}]", i);
+
+                let s = format!("{:?}", symbols[i].clone());
+                log::debug!("symbol: {}", s);
+
+                let reward_asset_addr = ethers::utils::to_checksum(&addresses[i].to_owned(), None);
+                log::debug!("reward_asset_addr: {:?}", reward_asset_addr);
+                let reward_asset = ctx
+                    .store
+                    .find_asset(&reward_asset_addr, &chef.chain, &chef.protocol)
+                    .await?;
+
+                if reward_asset.is_some() {
+                    let reward_asset_price = reward_asset.clone().unwrap().price;
+                    log::debug!("reward_asset_price: {:?}", reward_asset_price);
+
+                    (asset_price, asset_tvl) = source
+                        .asset_price_and_tvl(ctx, pid, asset.as_ref().unwrap(), total_lp)
+                        .await?;
+                    log::debug!("asset_price: {:?}", asset_price);
+
+                    let rewards_per_day: u128 = source
+                        .rewards_per_day(
+                            ap,
+                            reward_asset.as_ref().unwrap(),
+                            rewards_per_sec[i].as_u128() * 60 * 60 * 24,
+                        )
+                        .await?;
+
+                    if rewards_per_day != 0 {
+                        if !reward_asset_map.contains_key(&reward_asset.clone().unwrap().symbol) {
+                            reward_asset_map.insert(
+                                reward_asset.clone().unwrap().symbol,
+                                (
+                                    true,
+                                    rewards_per_day as f64
+                                        / constants::utils::TEN_I128
+                                            .pow(reward_asset.clone().unwrap().decimals)
+                                            as f64,
+                                    (rewards_per_day as f64
+                                        / constants::utils::TEN_I128
+                                            .pow(reward_asset.clone().unwrap().decimals)
+                                            as f64)
+                                        * reward_asset_price,
+                                    models::Freq::Daily.to_string(),
+                                ),
+                            );
+                        } else {
+                            let er = reward_asset_map
+                                .get(&reward_asset.clone().unwrap().symbol)
+                                .unwrap();
+                            reward_asset_map.insert(
+                                reward_asset.clone().unwrap().symbol,
+                                (
+                                    true,
+                                    er.1 + rewards_per_day as f64
+                                        / constants::utils::TEN_I128
+                                            .pow(reward_asset.clone().unwrap().decimals)
+                                            as f64,
+                                    er.2 + (rewards_per_day as f64
+                                        / constants::utils::TEN_I128
+                                            .pow(reward_asset.clone().unwrap().decimals)
+                                            as f64)
+                                        * reward_asset_price,
+                                    models::Freq::Daily.to_string(),
+                                ),
+                            );
+                        }
+
+                        // reward_apr/farm_apr/pool_apr
+                        log::debug!(
+                            "rewards/sec: {} rewards/day: {} asset_tvl: {}",
+                            rewards_per_sec[i].as_u128(),
+                            rewards_per_day,
+                            asset_tvl
+                        );
+
+                        let reward_apr = ((rewards_per_day as f64
+                            / constants::utils::TEN_I128
+                                .pow(decimals[i].as_u128().try_into().unwrap())
+                                as f64
+                            * reward_asset_price)
+                            / (asset_tvl as f64 * asset_price
+                                / constants::utils::TEN_I128.pow(18) as f64))
+                            * 365.0
+                            * 100.0;
+                        log::debug!("reward_apr: {}", reward_apr);
+                        if asset_tvl != 0 && asset_price != 0.0 {
+                            total_reward_apr += reward_apr;
+                        }
+                    }
+                }
+            }
+
+            for r in reward_asset_map.iter() {
+                rewards.push(models::Reward {
+                    amount: r.1 .1,
+                    asset: r.0.clone(),
+                    value_usd: r.1 .2,
+                    freq: models::Freq::Daily.to_string(),
+                });
+            }
+
+            // base_apr/trading_apr
+            log::debug!(
+                "pddq {:?} addr {:?} stable_owner_addr {:?}",
+                &constants::chef::PAIR_DAY_DATAS_QUERY.clone(),
+                asset.clone().unwrap().address.to_lowercase(),
+                pool.stable_owner_addr.clone().to_lowercase()
+            );
+            let mut base_apr = source
+                .base_apr(ctx, pid, asset.as_ref().unwrap(), &pool, asset_price)
+                .await?;
+            if !base_apr.is_finite() {
+                base_apr = 0.0;
+            }
+
+            let timestamp = Utc::now().to_string();
+
+            if !source.skip_farm(pid) {
+                log::debug!("chef v2 farm lastUpdatedAtUTC {}", timestamp.clone());
+
+                let farm = models::Farm {
+                    id: pid as i32,
+                    chef: chef.address.clone(),
+                    router,
+                    chain: chef.chain.clone(),
+                    protocol: chef.protocol.clone(),
+                    farm_type: farm_type.to_string(),
+                    farm_impl: farm_implementation.to_string(),
+                    asset: models::FarmAsset {
+                        symbol: asset.clone().unwrap().symbol,
+                        address: asset_addr.clone(),
+                        price: asset.clone().unwrap().price,
+                        logos: asset.clone().unwrap().logos,
+                        underlying_assets,
+                    },
+                    tvl: asset_tvl as f64 * asset_price / constants::utils::TEN_F64.powf(18.0),
+                    apr: models::APR {
+                        reward: total_reward_apr,
+                        base: base_apr,
+                    },
+                    rewards,
+                    alloc_point: ap,
+                    last_updated_at_utc: timestamp.clone(),
+                    block: chef.block,
+                    terms,
+                    ..Default::default()
+                };
+                ctx.store.upsert_farm(&farm).await?;
+            }
+        } else {
+            log::debug!("pdne");
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks a subgraph-priced LP against its pair's fair price, which catches a stale
+/// subgraph. The LP is repriced, and upserted, at the fair price when they're too far
+/// apart; any other asset, or one that can't be priced on-chain, is returned as is.
+async fn checked_lp_asset(ctx: &Context, chef: &Chef, asset: models::Asset) -> models::Asset {
+    if asset.price_source != models::PriceSource::Subgraph {
+        return asset;
+    }
+
+    let onchain = match lp::v2_lp_price(
+        &chef.calls,
+        ctx.store.as_ref(),
+        &chef.chain,
+        &chef.protocol,
+        &asset.address,
+    )
+    .await
+    {
+        Ok(onchain) => onchain,
+        Err(e) => {
+            log::warn!("couldn't price {} on-chain: {}", asset.address, e);
+            return asset;
+        }
+    };
+
+    let deviation = onchain.deviation(asset.price);
+    if deviation <= lp::MAX_LP_PRICE_DEVIATION {
+        return asset;
+    }
+
+    log::debug!(
+        "{} subgraph lp price {} is {:.2}% off its fair price {}, using the fair price",
+        asset.address,
+        asset.price,
+        deviation * 100.0,
+        onchain.fair
+    );
+    let repriced = models::Asset {
+        price: onchain.fair,
+        price_source: models::PriceSource::DexReserves,
+        price_confidence: models::PriceConfidence::Medium,
+        liquidity: onchain.fair * onchain.total_supply,
+        total_supply: onchain.total_supply,
+        last_updated_at_utc: Utc::now().to_string(),
+        ..asset.clone()
+    };
+    match ctx.store.upsert_asset(&repriced).await {
+        Ok(_) => repriced,
+        Err(e) => {
+            log::warn!("couldn't upsert repriced {}: {}", asset.address, e);
+            asset
+        }
+    }
+}
+
+/// Base APR of a pair from its recent day datas on the chef's subgraph, at the chef's
+/// `lp_fee`. Days the subgraph has no usd volume for are valued from the token volumes at
+/// the underlying assets' prices.
+pub async fn pair_day_datas_base_apr(
+    ctx: &Context,
+    chef: &Chef,
+    asset: &models::Asset,
+) -> Result<f64, Box<dyn std::error::Error>> {
+    #[derive(Serialize)]
+    pub struct Vars {
+        addr: String,
+        days: usize,
+    }
+    let vars = Vars {
+        addr: asset.address.to_lowercase(),
+        days: ctx.base_apr.days,
+    };
+
+    let pair_day_datas = match chef
+        .subgraph
+        .query_with_vars_unwrap::<subgraph::PairDayDatas, Vars>(
+            &constants::chef::PAIR_DAY_DATAS_QUERY.clone(),
+            vars,
+        )
+        .await
+    {
+        Ok(pair_day_datas) => pair_day_datas.pair_day_datas,
+        Err(e) => {
+            log::debug!("no pair day datas for {}: {:?}", asset.address, e);
+            return Ok(0.0);
+        }
+    };
+
+    let mut daily_volumes = vec![];
+    for pdd in pair_day_datas {
+        let dv: f64 = pdd.daily_volume_usd.parse().unwrap_or_default();
+        if dv != 0.0 {
+            daily_volumes.push(dv);
+            continue;
+        }
+
+        let mut dv = 0.0;
+        for (i, ua) in asset.underlying_assets.iter().take(2).enumerate() {
+            let ua_price = ctx
+                .store
+                .find_asset(&ua.address, &chef.chain, &chef.protocol)
+                .await?
+                .unwrap_or_default()
+                .price;
+            let token_volume = if i == 0 {
+                &pdd.daily_volume_token0
+            } else {
+                &pdd.daily_volume_token1
+            };
+            dv += token_volume.parse::<f64>().unwrap_or_default() * ua_price;
+        }
+        daily_volumes.push(dv);
+    }
+
+    Ok(apr::base_apr(
+        &daily_volumes,
+        asset.total_supply * asset.price,
+        chef.lp_fee,
+        ctx.base_apr,
+    ))
+}
+
+/// Upserts POOP, priced by the oracle, and the chef's WGLMR-POOP LP at its fair price.
+pub async fn upsert_poop_assets(
+    ctx: &Context,
+    chef: &Chef,
+    wglmr: &str,
+    poop: &str,
+    wglmr_poop_lp: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let poop_price = ctx.prices.price(&chef.chain, poop, None).await?;
+
+    let timestamp = Utc::now().to_string();
+
+    let poop_logo = format!(
+        "https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png",
+        "POOP"
+    );
+    let asset = models::Asset {
+        address: poop.to_string(),
+        chain: chef.chain.clone(),
+        protocol: chef.protocol.clone(),
+        name: "Raresama POOP".to_string(),
+        symbol: "POOP".to_string(),
+        decimals: 18,
+        logos: vec![poop_logo.clone()],
+        price: poop_price.usd,
+        price_source: poop_price.source,
+        price_confidence: poop_price.confidence,
+        peg: poop_price.peg,
+        liquidity: 1.0,
+        total_supply: 1.0,
+        is_lp: false,
+        fees_apr: 0.0,
+        underlying_assets: vec![],
+        underlying_assets_alloc: vec![],
+        last_updated_at_utc: timestamp.clone(),
+    };
+
+    ctx.store.upsert_asset(&asset).await?;
+
+    let wglmr_poop = lp::v2_lp_price(
+        &chef.calls,
+        ctx.store.as_ref(),
+        &chef.chain,
+        &chef.protocol,
+        wglmr_poop_lp,
+    )
+    .await?;
+    log::debug!("{} wglmr_poop {:?}", chef.protocol, wglmr_poop);
+
+    let wglmr_asset =
+        store::required_asset(ctx.store.as_ref(), wglmr, &chef.chain, &chef.protocol).await?;
+    let poop_asset =
+        store::required_asset(ctx.store.as_ref(), poop, &chef.chain, &chef.protocol).await?;
+
+    let timestamp = Utc::now().to_string();
+
+    let wglmr_logo = format!(
+        "https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png",
+        "WGLMR"
+    );
+    let lp_asset = models::Asset {
+        address: wglmr_poop_lp.to_string(),
+        chain: chef.chain.clone(),
+        protocol: chef.protocol.clone(),
+        name: "WGLMR-POOP LP".to_string(),
+        symbol: "WGLMR-POOP LP".to_string(),
+        decimals: 18,
+        logos: vec![wglmr_logo.clone(), poop_logo.clone()],
+        price: wglmr_poop.fair,
+        price_source: models::PriceSource::DexReserves,
+        price_confidence: models::PriceConfidence::High,
+        peg: None,
+        liquidity: wglmr_poop.fair * wglmr_poop.total_supply,
+        total_supply: wglmr_poop.total_supply,
+        is_lp: true,
+        fees_apr: 0.0,
+        underlying_assets: vec![
+            wglmr_asset.clone().underlying(),
+            poop_asset.clone().underlying(),
+        ],
+        underlying_assets_alloc: vec![],
+        last_updated_at_utc: timestamp.clone(),
+    };
+
+    ctx.store.upsert_asset(&lp_asset).await?;
+
+    Ok(())
+}
@@ -0,0 +1,280 @@
+use std::{collections::HashMap, sync::Arc};
+
+use ethers::{
+    prelude::{Address, U256},
+    providers::{Http, Middleware, Provider},
+};
+use futures::{future::LocalBoxFuture, stream, StreamExt};
+use gql_client::Client;
+
+use crate::apr;
+use crate::config;
+use crate::contracts;
+use crate::models;
+use crate::multicall;
+use crate::oracle;
+use crate::report;
+use crate::store;
+use crate::tokens;
+
+pub(crate) mod arthswap;
+pub(crate) mod beamswap;
+pub(crate) mod chef_v2;
+pub(crate) mod solarbeam;
+pub(crate) mod solarflare;
+pub(crate) mod stellaswap;
+pub(crate) mod sushi;
+pub(crate) mod zenlink;
+
+/// Read-only provider, every chef call is a view call so nothing needs signing.
+pub type ChainMiddleware = Provider<Http>;
+pub type ChainClient = Arc<ChainMiddleware>;
+
+/// The store every farm source reads assets from and writes farms to, the token
+/// metadata resolver, the price oracle and the report failed pids are recorded in.
+pub struct Context {
+    pub store: Arc<dyn store::Store>,
+    pub tokens: Arc<tokens::TokenResolver>,
+    pub prices: Arc<oracle::PriceOracle>,
+    pub report: Arc<report::Report>,
+    /// Days of volume base APRs are averaged over.
+    pub base_apr: apr::Window,
+}
+
+/// A deployed chef contract along with the chain, protocol and subgraph it belongs to.
+pub struct Chef {
+    pub contract: contracts::IChefV2<ChainMiddleware>,
+    pub address: String,
+    pub chain: String,
+    pub protocol: String,
+    pub version: String,
+    pub router: String,
+    pub subgraph: Client,
+    pub client: ChainClient,
+    /// Block every contract read of the run is made at, see `blocks`.
+    pub block: Option<u64>,
+    /// Batches and caches the chef's view calls, see `FarmSource::prefetch`.
+    pub calls: multicall::Multicall,
+    pub block_time: f64,
+    pub concurrency: usize,
+    pub farm_implementation: models::FarmImplementation,
+    pub pids: Option<Vec<u32>>,
+    /// Share of each swap LPs earn in the protocol's pairs, see `config::Fees`.
+    pub lp_fee: f64,
+    /// Same for the protocol's stable pools, when it has any configured.
+    pub stable_lp_fee: Option<f64>,
+    pub reward_vesting: Option<u64>,
+}
+
+impl Chef {
+    pub fn new(
+        config: &config::Config,
+        chef: &config::Chef,
+        client: ChainClient,
+        block: Option<u64>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let chain = config.chain(&chef.chain)?;
+        let contract =
+            contracts::IChefV2::new(chef.address.parse::<Address>()?, Arc::clone(&client));
+        let multicall = match chain.multicall.as_str() {
+            "" => None,
+            address => Some(address.parse::<Address>()?),
+        };
+        Ok(Chef {
+            contract,
+            address: chef.address.clone(),
+            chain: chef.chain.clone(),
+            protocol: chef.protocol.clone(),
+            version: chef.version.clone(),
+            router: chef.router.clone(),
+            subgraph: subgraph_client(&chef.subgraph),
+            calls: multicall::Multicall::new(Arc::clone(&client), multicall, block),
+            client,
+            block,
+            block_time: chain.block_time,
+            concurrency: chain.concurrency,
+            farm_implementation: chef.farm_implementation,
+            pids: chef.pids.clone(),
+            lp_fee: config.lp_fee(&chef.protocol, config::PoolKind::Amm)?,
+            stable_lp_fee: config.lp_fee(&chef.protocol, config::PoolKind::Stable).ok(),
+            reward_vesting: chef.reward_vesting,
+        })
+    }
+
+    /// Terms of the chef's farms that don't depend on the pool.
+    pub fn terms(&self) -> models::FarmTerms {
+        models::FarmTerms {
+            vesting: self.reward_vesting,
+            ..Default::default()
+        }
+    }
+}
+
+fn subgraph_client(url: &str) -> Client {
+    let mut headers = HashMap::new();
+    headers.insert("content-type", "application/json");
+    Client::new_with_headers(url, 60, headers)
+}
+
+/// A protocol/version specific reader of chef farms.
+pub trait FarmSource {
+    fn chef(&self) -> &Chef;
+
+    /// Upserts assets the source prices itself, before any of its farms are fetched.
+    fn fetch_assets<'a>(
+        &'a self,
+        _ctx: &'a Context,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Batches the view calls `fetch_farm` will make for `pids` into `chef().calls`, so
+    /// the farms are mostly read from the prefetched results.
+    fn prefetch<'a>(
+        &'a self,
+        _pids: &'a [u32],
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Upserts the farm at `pid`.
+    fn fetch_farm<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>>;
+
+    /// Upserts every farm listed on the chef, up to `concurrency` pids at a time.
+    /// A failing pid is recorded in the report and doesn't stop the others.
+    fn fetch_farms<'a>(
+        &'a self,
+        ctx: &'a Context,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = self.chef();
+            let pids = match chef.pids.clone() {
+                Some(pids) => pids,
+                None => {
+                    let pool_length: U256 = chef.calls.call(chef.contract.pool_length()).await?;
+                    log::debug!("pool_length {}", pool_length.as_u32());
+                    (0..pool_length.as_u32()).collect()
+                }
+            };
+
+            // a failed prefetch only means the calls are made one by one
+            if let Err(e) = self.prefetch(&pids).await {
+                log::warn!("{} {} prefetch failed: {}", chef.protocol, chef.version, e);
+            }
+
+            stream::iter(pids)
+                .for_each_concurrent(chef.concurrency, |pid| async move {
+                    log::debug!(
+                        "---------------------\n{} {} pid {}",
+                        chef.protocol.clone(),
+                        chef.version.clone(),
+                        pid
+                    );
+                    if let Err(e) = self.fetch_farm(ctx, pid).await {
+                        ctx.report
+                            .record(&chef.protocol, Some(&chef.chain), Some(pid), e);
+                    }
+                })
+                .await;
+
+            Ok(())
+        })
+    }
+}
+
+/// One read-only provider per configured chain whose rpc url env var is set.
+pub fn clients(
+    config: &config::Config,
+) -> Result<HashMap<String, ChainClient>, Box<dyn std::error::Error>> {
+    let mut clients: HashMap<String, ChainClient> = HashMap::new();
+    for chain in config.chains.iter() {
+        match dotenv::var(&chain.rpc_url_env) {
+            Ok(url) => {
+                let provider = Provider::<Http>::try_from(url)?;
+                clients.insert(chain.name.clone(), Arc::new(provider));
+            }
+            Err(_) => log::warn!("{} is not set, skipping {}", chain.rpc_url_env, chain.name),
+        }
+    }
+    Ok(clients)
+}
+
+/// The latest block of every chain, which a run's chef reads, and the LP reads of the
+/// price oracle and the arthswap pairs, are all made at so the farms' TVL, reserves and
+/// emissions agree with each other. A chain whose block can't
+/// be read is left out and its chefs read at the latest block per call.
+pub async fn blocks(clients: &HashMap<String, ChainClient>) -> HashMap<String, u64> {
+    let mut blocks = HashMap::new();
+    for (chain, client) in clients.iter() {
+        match client.get_block_number().await {
+            Ok(block) => {
+                log::debug!("{} reads pinned to block {}", chain, block);
+                blocks.insert(chain.clone(), block.as_u64());
+            }
+            Err(e) => log::warn!("couldn't get the {} block: {}", chain, e),
+        }
+    }
+    blocks
+}
+
+/// Builds a farm source for every chef in the config, in the order they are listed.
+/// A chef that can't be built, e.g. because its chain has no rpc client, is recorded in
+/// the report and left out.
+pub fn sources(
+    config: &config::Config,
+    clients: &HashMap<String, ChainClient>,
+    blocks: &HashMap<String, u64>,
+    report: &report::Report,
+) -> Vec<Box<dyn FarmSource>> {
+    let mut sources: Vec<Box<dyn FarmSource>> = vec![];
+    for c in config.chefs.iter() {
+        match source(config, c, clients, blocks) {
+            Ok(source) => sources.push(source),
+            Err(e) => report.record(&c.protocol, Some(&c.chain), None, e),
+        }
+    }
+    sources
+}
+
+fn source(
+    config: &config::Config,
+    c: &config::Chef,
+    clients: &HashMap<String, ChainClient>,
+    blocks: &HashMap<String, u64>,
+) -> Result<Box<dyn FarmSource>, Box<dyn std::error::Error>> {
+    let chain = config.chain(&c.chain)?;
+    let client = clients.get(&c.chain).ok_or_else(|| {
+        format!(
+            "{} is not set, skipping {} {}",
+            chain.rpc_url_env, c.protocol, c.version
+        )
+    })?;
+    let chef = Chef::new(config, c, Arc::clone(client), blocks.get(&c.chain).copied())?;
+    let stable_subgraph = || {
+        c.stable_subgraph
+            .as_deref()
+            .map(subgraph_client)
+            .ok_or_else(|| format!("{} {} has no stable_subgraph", c.protocol, c.version))
+    };
+
+    Ok(match c.adapter {
+        config::Adapter::Arthswap => Box::new(arthswap::Arthswap { chef }),
+        config::Adapter::Zenlink => Box::new(zenlink::Zenlink { chef }),
+        config::Adapter::SushiMiniChef => Box::new(sushi::SushiMiniChef { chef }),
+        config::Adapter::Beamswap => Box::new(beamswap::Beamswap { chef }),
+        config::Adapter::Solarflare => Box::new(solarflare::Solarflare { chef }),
+        config::Adapter::StellaswapV1 => Box::new(stellaswap::StellaSwapV1 { chef }),
+        config::Adapter::StellaswapV2 => Box::new(stellaswap::StellaSwapV2 {
+            chef,
+            stable_subgraph: stable_subgraph()?,
+        }),
+        config::Adapter::Solarbeam => Box::new(solarbeam::Solarbeam {
+            chef,
+            stable_subgraph: stable_subgraph()?,
+        }),
+    })
+}
@@ -0,0 +1,622 @@
+use std::sync::Arc;
+
+use chrono::prelude::Utc;
+use ethers::prelude::{Address, U256};
+use futures::future::LocalBoxFuture;
+use gql_client::Client;
+use serde::Serialize;
+
+use crate::apr;
+use crate::chef::chef_v2::{self, ChefV2, Pool};
+use crate::chef::{Chef, Context, FarmSource};
+use crate::constants;
+use crate::contracts;
+use crate::lp;
+use crate::models;
+use crate::store;
+use crate::subgraph;
+
+/// Pools whose LP is a Solarbeam stable swap token rather than a uniswap v2 pair.
+const STABLE_PIDS: [u32; 6] = [8, 9, 13, 16, 17, 25];
+
+pub struct Solarbeam {
+    pub chef: Chef,
+    pub stable_subgraph: Client,
+}
+
+impl FarmSource for Solarbeam {
+    fn chef(&self) -> &Chef {
+        &self.chef
+    }
+
+    fn prefetch<'a>(
+        &'a self,
+        pids: &'a [u32],
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::prefetch(&self.chef, pids))
+    }
+
+    fn fetch_farm<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::fetch_farm(self, ctx, pid))
+    }
+}
+
+impl ChefV2 for Solarbeam {
+    fn pool<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+        lp_token: Address,
+    ) -> LocalBoxFuture<'a, Result<Pool, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+            let mut pool = Pool::standard(chef);
+
+            if STABLE_PIDS.contains(&pid) {
+                pool.farm_type = models::FarmType::StableAmm;
+
+                let stable_asset =
+                    contracts::IStableLpToken::new(lp_token, Arc::clone(&chef.client));
+                let symbol: String = chef.calls.call(stable_asset.symbol()).await?;
+
+                let owner_addr: Address = chef.calls.call(stable_asset.owner()).await?;
+                pool.stable_owner_addr = ethers::utils::to_checksum(&owner_addr.to_owned(), None);
+                pool.router = pool.stable_owner_addr.clone();
+
+                let owner =
+                    contracts::IStableLpTokenOwner::new(owner_addr, Arc::clone(&chef.client));
+                let stable_lp_underlying_tokens: Vec<Address> =
+                    chef.calls.call(owner.get_tokens()).await?;
+                let stable_lp_underlying_balances =
+                    chef.calls.call(owner.get_token_balances()).await?;
+                log::debug!(
+                    "stable_lp_underlying_tokens: {:#?}",
+                    stable_lp_underlying_tokens
+                );
+                log::debug!(
+                    "stable_lp_underlying_balances: {:#?}",
+                    stable_lp_underlying_balances
+                );
+
+                let stable_lp = lp::stable_lp_price(
+                    &chef.calls,
+                    ctx.store.as_ref(),
+                    &chef.chain,
+                    &chef.protocol,
+                    lp_token,
+                )
+                .await?;
+                log::debug!("{} stable_lp {:?}", symbol, stable_lp);
+
+                let busd_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::BUSD,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                let usdc_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::USDC,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                let usdt_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::USDT,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                let frax_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::FRAX,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                let mai_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::MAI,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                let mim_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::MIM,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                let wbtc_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::WBTC,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                let xckbtc_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::XCKBTC,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                let xcksm_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::XCKSM,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                let stksm_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::STKSM,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+
+                // TODO: calculate underlyingAssetsAlloc
+
+                if symbol == "3pool".to_string() {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: usdc_asset.clone().symbol,
+                            address: usdc_asset.clone().address,
+                            decimals: usdc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: busd_asset.clone().symbol,
+                            address: busd_asset.clone().address,
+                            decimals: busd_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdt_asset.clone().symbol,
+                            address: usdt_asset.clone().address,
+                            decimals: usdt_asset.clone().decimals,
+                        },
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::solarbeam_on_moonriver::_3POOL.to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "Solarbeam Stable AMM - USD Pool".to_string(),
+                        symbol: "3pool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "FRAX-3pool".to_string() {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: frax_asset.clone().symbol,
+                            address: frax_asset.clone().address,
+                            decimals: frax_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: "3pool".to_string(),
+                            address: ethers::utils::to_checksum(
+                                &stable_lp_underlying_tokens[1].to_owned(),
+                                None,
+                            ),
+                            decimals: 18,
+                        },
+                        // bson!({
+                        //     "symbol": usdc_asset.clone().symbol,
+                        //     "address":  usdc_asset.clone().address,
+                        //     "decimals": usdc_asset.clone().decimals,
+                        // }),
+                        // bson!({
+                        //     "symbol": busd_asset.clone().symbol,
+                        //     "address":  busd_asset.clone().address,
+                        //     "decimals": busd_asset.clone().decimals,
+                        // }),
+                        // bson!({
+                        //     "symbol": usdt_asset.clone().symbol,
+                        //     "address":  usdt_asset.clone().address,
+                        //     "decimals": usdt_asset.clone().decimals,
+                        // }),
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::solarbeam_on_moonriver::FRAX_3POOL
+                            .to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "Solarbeam Stable AMM - FRAX Pool".to_string(),
+                        symbol: "FRAX-3pool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            frax_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "MAI-3pool".to_string() {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: mai_asset.clone().symbol,
+                            address: mai_asset.clone().address,
+                            decimals: mai_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: "3pool".to_string(),
+                            address: ethers::utils::to_checksum(
+                                &stable_lp_underlying_tokens[1].to_owned(),
+                                None,
+                            ),
+                            decimals: 18,
+                        },
+                        // bson!({
+                        //     "symbol": usdc_asset.clone().symbol,
+                        //     "address":  usdc_asset.clone().address,
+                        //     "decimals": usdc_asset.clone().decimals,
+                        // }),
+                        // bson!({
+                        //     "symbol": busd_asset.clone().symbol,
+                        //     "address":  busd_asset.clone().address,
+                        //     "decimals": busd_asset.clone().decimals,
+                        // }),
+                        // bson!({
+                        //     "symbol": usdt_asset.clone().symbol,
+                        //     "address":  usdt_asset.clone().address,
+                        //     "decimals": usdt_asset.clone().decimals,
+                        // }),
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::solarbeam_on_moonriver::MAI_3POOL
+                            .to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "Solarbeam Stable AMM - MAI Pool".to_string(),
+                        symbol: "MAI-3pool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            mai_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "MIM-3pool".to_string() {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: mim_asset.clone().symbol,
+                            address: mim_asset.clone().address,
+                            decimals: mim_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: "3pool".to_string(),
+                            address: ethers::utils::to_checksum(
+                                &stable_lp_underlying_tokens[1].to_owned(),
+                                None,
+                            ),
+                            decimals: 18,
+                        },
+                        // bson!({
+                        //     "symbol": usdc_asset.clone().symbol,
+                        //     "address":  usdc_asset.clone().address,
+                        //     "decimals": usdc_asset.clone().decimals,
+                        // }),
+                        // bson!({
+                        //     "symbol": busd_asset.clone().symbol,
+                        //     "address":  busd_asset.clone().address,
+                        //     "decimals": busd_asset.clone().decimals,
+                        // }),
+                        // bson!({
+                        //     "symbol": usdt_asset.clone().symbol,
+                        //     "address":  usdt_asset.clone().address,
+                        //     "decimals": usdt_asset.clone().decimals,
+                        // }),
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::solarbeam_on_moonriver::MIM_3POOL
+                            .to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "Solarbeam Stable AMM - MIM Pool".to_string(),
+                        symbol: "MIM-3pool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            mim_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "kBTC-BTC".to_string() {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: xckbtc_asset.clone().symbol,
+                            address: xckbtc_asset.clone().address,
+                            decimals: xckbtc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: wbtc_asset.clone().symbol,
+                            address: wbtc_asset.clone().address,
+                            decimals: wbtc_asset.clone().decimals,
+                        },
+                    ];
+
+                    let pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let pool_price = stable_lp.price;
+                    log::debug!("pool_price {}", pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::solarbeam_on_moonriver::KBTC_BTC.to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "Solarbeam Stable AMM - kBTC Pool".to_string(),
+                        symbol: "kBTC-BTC".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            xckbtc_asset
+                                .clone()
+                                .logos
+                                .get(0)
+                                .cloned()
+                                .unwrap_or_default(),
+                            wbtc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "stKSM".to_string() {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: xcksm_asset.clone().symbol,
+                            address: xcksm_asset.clone().address,
+                            decimals: xcksm_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: stksm_asset.clone().symbol,
+                            address: stksm_asset.clone().address,
+                            decimals: stksm_asset.clone().decimals,
+                        },
+                    ];
+
+                    let pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let pool_price = stable_lp.price;
+                    log::debug!("pool_price {}", pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::solarbeam_on_moonriver::STKSM_POOL
+                            .to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "Solarbeam Stable AMM - stKSM Pool".to_string(),
+                        symbol: "stKSM".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            xcksm_asset
+                                .clone()
+                                .logos
+                                .get(0)
+                                .cloned()
+                                .unwrap_or_default(),
+                            stksm_asset
+                                .clone()
+                                .logos
+                                .get(0)
+                                .cloned()
+                                .unwrap_or_default(),
+                        ],
+                        price: pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                }
+            }
+
+            Ok(pool)
+        })
+    }
+
+    fn asset_price_and_tvl<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+        asset: &'a models::Asset,
+        total_lp: U256,
+    ) -> LocalBoxFuture<'a, Result<(f64, u128), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let mut asset_price = asset.price;
+            // pid 38 stakes SOLAR itself
+            if pid == 38 {
+                let solar = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::SOLAR,
+                    "moonriver",
+                    "solarbeam",
+                )
+                .await?;
+                asset_price = solar.price;
+            }
+
+            Ok((asset_price, total_lp.as_u128()))
+        })
+    }
+
+    fn base_apr<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+        asset: &'a models::Asset,
+        pool: &'a Pool,
+        _asset_price: f64,
+    ) -> LocalBoxFuture<'a, Result<f64, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            if !STABLE_PIDS.contains(&pid) {
+                return chef_v2::pair_day_datas_base_apr(ctx, &self.chef, asset).await;
+            }
+
+            #[derive(Serialize)]
+            pub struct Vars {
+                addr: String,
+                days: usize,
+            }
+            log::debug!("stablesolarbeam");
+            let vars = Vars {
+                addr: pool.stable_owner_addr.clone().to_lowercase(),
+                days: ctx.base_apr.days,
+            };
+            let swap_data = self
+                .stable_subgraph
+                .query_with_vars_unwrap::<subgraph::SolarbeamStableData, Vars>(
+                    &constants::chef::SOLARBEAM_STABLE_SWAPS_DAY_DATA_QUERY.clone(),
+                    vars,
+                )
+                .await;
+
+            let swap_data = match swap_data {
+                Ok(swap_data) => swap_data,
+                Err(e) => {
+                    log::warn!("solarbeam swap_data err {:?}", e);
+                    return Ok(0.0);
+                }
+            };
+            // the stable subgraph's volumes are in LP tokens
+            let daily_volumes: Vec<f64> = swap_data
+                .swap
+                .daily_data
+                .iter()
+                .map(|dd| dd.volume.parse::<f64>().unwrap_or_default() * asset.price)
+                .collect();
+            log::debug!("solarbeam stable daily_volumes {:?}", daily_volumes);
+
+            Ok(apr::base_apr(
+                &daily_volumes,
+                asset.total_supply * asset.price,
+                self.chef.stable_lp_fee.ok_or_else(|| {
+                    format!("no stable fees configured for {}", self.chef.protocol)
+                })?,
+                ctx.base_apr,
+            ))
+        })
+    }
+
+    fn skip_farm(&self, pid: u32) -> bool {
+        pid == 39 || pid == 40 || pid == 43
+    }
+}
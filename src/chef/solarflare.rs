@@ -0,0 +1,89 @@
+use ethers::prelude::Address;
+use futures::future::LocalBoxFuture;
+
+use crate::chef::chef_v2::{self, ChefV2, Pool};
+use crate::chef::{Chef, Context, FarmSource};
+use crate::constants;
+use crate::models;
+use crate::store;
+
+pub struct Solarflare {
+    pub chef: Chef,
+}
+
+impl FarmSource for Solarflare {
+    fn chef(&self) -> &Chef {
+        &self.chef
+    }
+
+    fn prefetch<'a>(
+        &'a self,
+        pids: &'a [u32],
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::prefetch(&self.chef, pids))
+    }
+
+    fn fetch_farm<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::fetch_farm(self, ctx, pid))
+    }
+}
+
+impl ChefV2 for Solarflare {
+    fn pool<'a>(
+        &'a self,
+        _ctx: &'a Context,
+        pid: u32,
+        _lp_token: Address,
+    ) -> LocalBoxFuture<'a, Result<Pool, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let mut pool = Pool::standard(&self.chef);
+
+            if pid == 3 {
+                pool.farm_type = models::FarmType::SingleStaking;
+                pool.underlying_assets = vec![models::UnderlyingAsset {
+                    symbol: "WGLMR".to_string(),
+                    address: constants::addresses::solarflare_on_moonbeam::WGLMR.to_string(),
+                    decimals: 18,
+                }];
+            }
+
+            Ok(pool)
+        })
+    }
+
+    fn base_apr<'a>(
+        &'a self,
+        ctx: &'a Context,
+        _pid: u32,
+        asset: &'a models::Asset,
+        _pool: &'a Pool,
+        _asset_price: f64,
+    ) -> LocalBoxFuture<'a, Result<f64, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let mut base_apr = chef_v2::pair_day_datas_base_apr(ctx, &self.chef, asset).await?;
+
+            // the subgraph's USD volumes are denominated in nomad USDC
+            let usdc_nomad_solarflare = store::required_asset(
+                ctx.store.as_ref(),
+                constants::addresses::beamswap_on_moonbeam::USDC,
+                "moonbeam",
+                "solarflare",
+            )
+            .await?;
+            if usdc_nomad_solarflare.price <= 0.0 {
+                return Err(format!(
+                    "nomad USDC {} isn't priced, solarflare base APRs can't be converted",
+                    usdc_nomad_solarflare.address
+                )
+                .into());
+            }
+            base_apr /= usdc_nomad_solarflare.price;
+
+            Ok(base_apr)
+        })
+    }
+}
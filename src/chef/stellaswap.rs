@@ -0,0 +1,883 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::prelude::Utc;
+use ethers::prelude::{Address, U256};
+use futures::future::LocalBoxFuture;
+use gql_client::Client;
+use serde::Serialize;
+
+use crate::apr;
+use crate::chef::chef_v2::{self, ChefV2, Pool, PoolInfo};
+use crate::chef::{Chef, Context, FarmSource};
+use crate::constants;
+use crate::contracts;
+use crate::lp;
+use crate::models;
+use crate::store;
+use crate::subgraph;
+
+/// Pools whose LP is a StellaSwap stable swap token rather than a uniswap v2 pair.
+const STABLE_PIDS: [u32; 7] = [31, 33, 34, 35, 37, 38, 39];
+/// Stable pools with day volumes on the StellaSwap stable subgraph.
+const STABLE_SWAP_VOLUME_PIDS: [u32; 4] = [31, 33, 34, 35];
+
+/// StellaSwap's v1 distributor, emitting STELLA per block.
+pub struct StellaSwapV1 {
+    pub chef: Chef,
+}
+
+impl FarmSource for StellaSwapV1 {
+    fn chef(&self) -> &Chef {
+        &self.chef
+    }
+
+    fn prefetch<'a>(
+        &'a self,
+        pids: &'a [u32],
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::prefetch(&self.chef, pids))
+    }
+
+    fn fetch_farm<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+            let router = chef.router.clone();
+
+            let PoolInfo {
+                lp_token,
+                alloc_point: ap,
+                total_lp,
+                terms,
+            } = chef_v2::pool_info(chef, pid).await?;
+
+            if ap == 0 {
+                return chef_v2::upsert_inactive_farm(ctx, chef, pid, ap).await;
+            }
+
+            let farm_type = models::FarmType::StandardAmm;
+            let farm_implementation = chef.farm_implementation;
+
+            // chef v1
+            let asset_addr = ethers::utils::to_checksum(&lp_token.to_owned(), None);
+            log::debug!("asset_addr: {:?}", asset_addr.clone());
+
+            let stella_chef_v1_address = chef.address.parse::<Address>()?;
+            let stella_chef_v1 = contracts::IStellaDistributorV1::new(
+                stella_chef_v1_address,
+                Arc::clone(&chef.client),
+            );
+            let asset = ctx
+                .store
+                .find_asset(&asset_addr, &chef.chain, &chef.protocol)
+                .await?;
+
+            let asset_price: f64;
+            let asset_tvl: u128;
+
+            let mut rewards = vec![];
+            // <symbol, (exists, amount, valueUSD, freq)>
+            let reward_asset_map: HashMap<String, (bool, f64, f64, String)> = HashMap::new();
+
+            if asset.is_some() {
+                log::debug!("asset: {:?}", asset.clone().unwrap().symbol);
+                let spb: U256 = chef.calls.call(stella_chef_v1.stella_per_block()).await?;
+                let tap: U256 = chef.calls.call(stella_chef_v1.total_alloc_point()).await?;
+
+                let average_block_time = 12.4;
+                let stella = ctx
+                    .store
+                    .find_asset(
+                        constants::addresses::stellaswap_on_moonbeam::STELLA,
+                        &chef.chain,
+                        &chef.protocol,
+                    )
+                    .await?;
+
+                if stella.is_some() {
+                    let reward_asset_price = stella.clone().unwrap().price;
+                    log::debug!("reward_asset_price: {:?}", reward_asset_price);
+
+                    asset_price = asset.clone().unwrap().price;
+                    log::debug!("asset_price: {:?}", asset_price);
+
+                    let rewards_per_sec: f64 = (spb.as_u128() as f64
+                        * (ap as f64 / tap.as_u128() as f64))
+                        / average_block_time;
+                    let rewards_per_day: f64 = rewards_per_sec * 60.0 * 60.0 * 24.0;
+                    asset_tvl = total_lp.as_u128();
+
+                    if rewards_per_day != 0.0 {
+                        rewards.push(models::Reward {
+                            amount: rewards_per_day as f64
+                                / constants::utils::TEN_I128.pow(stella.clone().unwrap().decimals)
+                                    as f64,
+                            asset: stella.clone().unwrap().symbol,
+                            value_usd: (rewards_per_day as f64
+                                / constants::utils::TEN_I128.pow(stella.clone().unwrap().decimals)
+                                    as f64)
+                                * reward_asset_price,
+                            freq: models::Freq::Daily.to_string(),
+                        });
+                    }
+
+                    // reward_apr/farm_apr/pool_apr
+                    log::debug!(
+                        "rewards/sec: {} rewards/day: {} asset_tvl: {}",
+                        rewards_per_sec,
+                        rewards_per_day,
+                        asset_tvl
+                    );
+                    let mut reward_apr = 0.0;
+
+                    if asset_tvl != 0 && asset_price != 0.0 {
+                        reward_apr = ((rewards_per_day as f64 * reward_asset_price)
+                            / (asset_tvl as f64 * asset_price))
+                            * 365.0
+                            * 100.0;
+                    }
+                    log::debug!("reward_apr: {}", reward_apr);
+
+                    let mut uas = vec![];
+                    for ua in asset.clone().unwrap().underlying_assets {
+                        uas.push(models::UnderlyingAsset {
+                            symbol: ua.symbol,
+                            address: ua.address,
+                            decimals: ua.decimals,
+                        })
+                    }
+
+                    // base_apr/trading_apr
+                    #[derive(Serialize)]
+                    pub struct Vars {
+                        addr: String,
+                        days: usize,
+                    }
+                    let vars = Vars {
+                        addr: asset.clone().unwrap().address.to_lowercase(),
+                        days: ctx.base_apr.days,
+                    };
+                    let pair_day_datas = chef
+                        .subgraph
+                        .query_with_vars_unwrap::<subgraph::PairDayDatas, Vars>(
+                            &constants::chef::PAIR_DAY_DATAS_QUERY.clone(),
+                            vars,
+                        )
+                        .await;
+                    let daily_volumes: Vec<f64> = pair_day_datas
+                        .map(|pdd| pdd.pair_day_datas)
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|pdd| pdd.daily_volume_usd.parse().unwrap_or_default())
+                        .collect();
+                    let base_apr = apr::base_apr(
+                        &daily_volumes,
+                        asset.clone().unwrap_or_default().total_supply
+                            * asset.clone().unwrap_or_default().price,
+                        chef.lp_fee,
+                        ctx.base_apr,
+                    );
+
+                    let timestamp = Utc::now().to_string();
+
+                    log::debug!("chef v1 farm lastUpdatedAtUTC {}", timestamp.clone());
+
+                    let farm = models::Farm {
+                        id: pid as i32,
+                        chef: chef.address.clone(),
+                        router,
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        farm_type: farm_type.to_string(),
+                        farm_impl: farm_implementation.to_string(),
+                        asset: models::FarmAsset {
+                            symbol: asset.clone().unwrap().symbol,
+                            address: asset_addr.clone(),
+                            price: asset.clone().unwrap().price,
+                            logos: asset.clone().unwrap().logos,
+                            underlying_assets: uas,
+                        },
+                        tvl: asset_tvl as f64 * asset_price / constants::utils::TEN_F64.powf(18.0),
+                        apr: models::APR {
+                            reward: reward_apr,
+                            base: base_apr,
+                        },
+                        rewards,
+                        alloc_point: ap,
+                        last_updated_at_utc: timestamp.clone(),
+                        block: chef.block,
+                        terms,
+                        ..Default::default()
+                    };
+                    ctx.store.upsert_farm(&farm).await?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// StellaSwap's v2 distributor, which also hosts the stable swap pools.
+pub struct StellaSwapV2 {
+    pub chef: Chef,
+    pub stable_subgraph: Client,
+}
+
+impl FarmSource for StellaSwapV2 {
+    fn chef(&self) -> &Chef {
+        &self.chef
+    }
+
+    fn fetch_assets<'a>(
+        &'a self,
+        ctx: &'a Context,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::upsert_poop_assets(
+            ctx,
+            &self.chef,
+            constants::addresses::stellaswap_on_moonbeam::WGLMR,
+            constants::addresses::stellaswap_on_moonbeam::POOP,
+            constants::addresses::stellaswap_on_moonbeam::WGLMR_POOP_LP,
+        ))
+    }
+
+    fn prefetch<'a>(
+        &'a self,
+        pids: &'a [u32],
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::prefetch(&self.chef, pids))
+    }
+
+    fn fetch_farm<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(chef_v2::fetch_farm(self, ctx, pid))
+    }
+}
+
+impl ChefV2 for StellaSwapV2 {
+    fn pool<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+        lp_token: Address,
+    ) -> LocalBoxFuture<'a, Result<Pool, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+            let mut pool = Pool::standard(chef);
+
+            if STABLE_PIDS.contains(&pid) {
+                pool.farm_type = models::FarmType::StableAmm;
+
+                let stable_asset =
+                    contracts::IStableLpToken::new(lp_token, Arc::clone(&chef.client));
+                let symbol: String = chef.calls.call(stable_asset.symbol()).await?;
+
+                let owner_addr: Address = chef.calls.call(stable_asset.owner()).await?;
+                pool.stable_owner_addr = ethers::utils::to_checksum(&owner_addr.to_owned(), None);
+                pool.router = pool.stable_owner_addr.clone();
+
+                let owner =
+                    contracts::IStableLpTokenOwner::new(owner_addr, Arc::clone(&chef.client));
+                let stable_lp_underlying_tokens: Vec<Address> =
+                    chef.calls.call(owner.get_tokens()).await?;
+                let stable_lp_underlying_balances =
+                    chef.calls.call(owner.get_token_balances()).await?;
+                log::debug!(
+                    "stable_lp_underlying_tokens: {:#?}",
+                    stable_lp_underlying_tokens
+                );
+                log::debug!(
+                    "stable_lp_underlying_balances: {:#?}",
+                    stable_lp_underlying_balances
+                );
+
+                let stable_lp = lp::stable_lp_price(
+                    &chef.calls,
+                    ctx.store.as_ref(),
+                    &chef.chain,
+                    &chef.protocol,
+                    lp_token,
+                )
+                .await?;
+                log::debug!("{} stable_lp {:?}", symbol, stable_lp);
+
+                let busd_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::stellaswap_on_moonbeam::BUSD,
+                    "moonbeam",
+                    "stellaswap",
+                )
+                .await?;
+                let usdc_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::stellaswap_on_moonbeam::USDC,
+                    "moonbeam",
+                    "stellaswap",
+                )
+                .await?;
+                let usdt_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::stellaswap_on_moonbeam::USDT,
+                    "moonbeam",
+                    "stellaswap",
+                )
+                .await?;
+                let frax_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::stellaswap_on_moonbeam::FRAX,
+                    "moonbeam",
+                    "stellaswap",
+                )
+                .await?;
+                let mai_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::stellaswap_on_moonbeam::MAI,
+                    "moonbeam",
+                    "stellaswap",
+                )
+                .await?;
+                let athusd_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::stellaswap_on_moonbeam::ATH_USD,
+                    "moonbeam",
+                    "stellaswap",
+                )
+                .await?;
+                let axlusdc_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::stellaswap_on_moonbeam::AXL_USDC,
+                    "moonbeam",
+                    "stellaswap",
+                )
+                .await?;
+
+                if symbol == "stella4pool".to_string() {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: usdc_asset.clone().symbol,
+                            address: usdc_asset.clone().address,
+                            decimals: usdc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdt_asset.clone().symbol,
+                            address: usdt_asset.clone().address,
+                            decimals: usdt_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: busd_asset.clone().symbol,
+                            address: busd_asset.clone().address,
+                            decimals: busd_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: frax_asset.clone().symbol,
+                            address: frax_asset.clone().address,
+                            decimals: frax_asset.clone().decimals,
+                        },
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::stellaswap_on_moonbeam::_4POOL.to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "StellaSwap 4pool".to_string(),
+                        symbol: "4pool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            frax_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "stellaMAI-4pool" {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: mai_asset.clone().symbol,
+                            address: mai_asset.clone().address,
+                            decimals: mai_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdc_asset.clone().symbol,
+                            address: usdc_asset.clone().address,
+                            decimals: usdc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdt_asset.clone().symbol,
+                            address: usdt_asset.clone().address,
+                            decimals: usdt_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: busd_asset.clone().symbol,
+                            address: busd_asset.clone().address,
+                            decimals: busd_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: frax_asset.clone().symbol,
+                            address: frax_asset.clone().address,
+                            decimals: frax_asset.clone().decimals,
+                        },
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::stellaswap_on_moonbeam::MAI_4POOL
+                            .to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "StellaSwap MAI-4pool".to_string(),
+                        symbol: "MAI-4pool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            mai_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            frax_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "stella-athUSD-4pool" {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: athusd_asset.clone().symbol,
+                            address: athusd_asset.clone().address,
+                            decimals: athusd_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdc_asset.clone().symbol,
+                            address: usdc_asset.clone().address,
+                            decimals: usdc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdt_asset.clone().symbol,
+                            address: usdt_asset.clone().address,
+                            decimals: usdt_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: busd_asset.clone().symbol,
+                            address: busd_asset.clone().address,
+                            decimals: busd_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: frax_asset.clone().symbol,
+                            address: frax_asset.clone().address,
+                            decimals: frax_asset.clone().decimals,
+                        },
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::stellaswap_on_moonbeam::ATH_USD_4POOL
+                            .to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "StellaSwap athUSD-4pool".to_string(),
+                        symbol: "athUSD-4pool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            athusd_asset
+                                .clone()
+                                .logos
+                                .get(0)
+                                .cloned()
+                                .unwrap_or_default(),
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            frax_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "stella-axlUSDC-4pool" {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: axlusdc_asset.clone().symbol,
+                            address: axlusdc_asset.clone().address,
+                            decimals: axlusdc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdc_asset.clone().symbol,
+                            address: usdc_asset.clone().address,
+                            decimals: usdc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdt_asset.clone().symbol,
+                            address: usdt_asset.clone().address,
+                            decimals: usdt_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: busd_asset.clone().symbol,
+                            address: busd_asset.clone().address,
+                            decimals: busd_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: frax_asset.clone().symbol,
+                            address: frax_asset.clone().address,
+                            decimals: frax_asset.clone().decimals,
+                        },
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::stellaswap_on_moonbeam::AXL_USDC_4POOL
+                            .to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "StellaSwap axlUSDC-4pool".to_string(),
+                        symbol: "axlUSDC-4pool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            axlusdc_asset
+                                .clone()
+                                .logos
+                                .get(0)
+                                .cloned()
+                                .unwrap_or_default(),
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            frax_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "stella-tripool" {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: usdc_asset.clone().symbol,
+                            address: usdc_asset.clone().address,
+                            decimals: usdc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdt_asset.clone().symbol,
+                            address: usdt_asset.clone().address,
+                            decimals: usdt_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: frax_asset.clone().symbol,
+                            address: frax_asset.clone().address,
+                            decimals: frax_asset.clone().decimals,
+                        },
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::stellaswap_on_moonbeam::TRI_POOL.to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "StellaSwap Tripool".to_string(),
+                        symbol: "tripool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            frax_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "stella-axlDualPool" {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: axlusdc_asset.clone().symbol,
+                            address: axlusdc_asset.clone().address,
+                            decimals: axlusdc_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: usdc_asset.clone().symbol,
+                            address: usdc_asset.clone().address,
+                            decimals: usdc_asset.clone().decimals,
+                        },
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::stellaswap_on_moonbeam::AXL_DUAL_POOL
+                            .to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "StellaSwap Axelar Dual Pool".to_string(),
+                        symbol: "axlDualPool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            axlusdc_asset
+                                .clone()
+                                .logos
+                                .get(0)
+                                .cloned()
+                                .unwrap_or_default(),
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                } else if symbol == "stellaMAI-tripool" {
+                    pool.underlying_assets = vec![
+                        models::UnderlyingAsset {
+                            symbol: mai_asset.clone().symbol,
+                            address: mai_asset.clone().address,
+                            decimals: mai_asset.clone().decimals,
+                        },
+                        models::UnderlyingAsset {
+                            symbol: "tripool".to_string(),
+                            address: ethers::utils::to_checksum(
+                                &stable_lp_underlying_tokens[1].to_owned(),
+                                None,
+                            ),
+                            decimals: 18,
+                        },
+                        // bson!({
+                        //     "symbol": usdc_asset.clone().symbol,
+                        //     "address":  usdc_asset.clone().address,
+                        //     "decimals": usdc_asset.clone().decimals,
+                        // }),
+                        // bson!({
+                        //     "symbol": usdt_asset.clone().symbol,
+                        //     "address":  usdt_asset.clone().address,
+                        //     "decimals": usdt_asset.clone().decimals,
+                        // }),
+                        // bson!({
+                        //     "symbol": frax_asset.clone().symbol,
+                        //     "address":  frax_asset.clone().address,
+                        //     "decimals": frax_asset.clone().decimals,
+                        // }),
+                    ];
+
+                    let usd_pool_liq = stable_lp.liquidity;
+                    let ts = stable_lp.total_supply;
+                    let usd_pool_price = stable_lp.price;
+                    log::debug!("usd_pool_price {}", usd_pool_price);
+
+                    let timestamp = Utc::now().to_string();
+
+                    let asset = models::Asset {
+                        address: constants::addresses::stellaswap_on_moonbeam::MAI_TRI_POOL
+                            .to_string(),
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        name: "StellaSwap MAI Tripool".to_string(),
+                        symbol: "MAI-tripool".to_string(),
+                        decimals: 18,
+                        logos: vec![
+                            mai_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                            frax_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        ],
+                        price: usd_pool_price,
+                        price_source: models::PriceSource::DexReserves,
+                        price_confidence: models::PriceConfidence::High,
+                        peg: None,
+                        liquidity: usd_pool_liq,
+                        total_supply: ts,
+                        is_lp: true,
+                        fees_apr: 0.0,
+                        underlying_assets: pool.underlying_assets.clone(),
+                        underlying_assets_alloc: vec![],
+                        last_updated_at_utc: timestamp.clone(),
+                    };
+
+                    ctx.store.upsert_asset(&asset).await?;
+                }
+            }
+
+            Ok(pool)
+        })
+    }
+
+    fn rewards_per_day<'a>(
+        &'a self,
+        alloc_point: u32,
+        reward_asset: &'a models::Asset,
+        rewards_per_day: u128,
+    ) -> LocalBoxFuture<'a, Result<u128, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            if reward_asset.symbol != "STELLA" {
+                return Ok(rewards_per_day);
+            }
+
+            // poolRewardsPerSec doesn't report STELLA emissions on this chef
+            let chef = &self.chef;
+            let stella_per_sec: U256 = chef.calls.call(chef.contract.stella_per_sec()).await?;
+            let total_alloc_point: U256 =
+                chef.calls.call(chef.contract.total_alloc_point()).await?;
+
+            Ok(
+                (alloc_point as u128) * (60 * 60 * 24 * stella_per_sec.as_u128())
+                    / total_alloc_point.as_u128(),
+            )
+        })
+    }
+
+    fn base_apr<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+        asset: &'a models::Asset,
+        pool: &'a Pool,
+        _asset_price: f64,
+    ) -> LocalBoxFuture<'a, Result<f64, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            if !STABLE_SWAP_VOLUME_PIDS.contains(&pid) {
+                return chef_v2::pair_day_datas_base_apr(ctx, &self.chef, asset).await;
+            }
+
+            #[derive(Serialize)]
+            pub struct Vars {
+                addr: String,
+                days: usize,
+            }
+            log::debug!("stablestellaswap");
+            let vars = Vars {
+                addr: pool.stable_owner_addr.clone().to_lowercase(),
+                days: ctx.base_apr.days,
+            };
+            let swap_data = self
+                .stable_subgraph
+                .query_with_vars_unwrap::<subgraph::StellaStableData, Vars>(
+                    &constants::chef::STELLASWAP_STABLE_SWAPS_DAY_DATA_QUERY.clone(),
+                    vars,
+                )
+                .await;
+
+            let swap_data = match swap_data {
+                Ok(swap_data) => swap_data,
+                Err(e) => {
+                    log::warn!("swap_dataerr {:?}", e);
+                    return Ok(0.0);
+                }
+            };
+            // the stable subgraph's volumes are in LP tokens
+            let daily_volumes: Vec<f64> = swap_data
+                .swap
+                .daily_volumes
+                .iter()
+                .map(|dv| dv.volume.parse::<f64>().unwrap_or_default() * asset.price)
+                .collect();
+            log::debug!("stellaswap stable daily_volumes {:?}", daily_volumes);
+
+            Ok(apr::base_apr(
+                &daily_volumes,
+                asset.total_supply * asset.price,
+                self.chef.stable_lp_fee.ok_or_else(|| {
+                    format!("no stable fees configured for {}", self.chef.protocol)
+                })?,
+                ctx.base_apr,
+            ))
+        })
+    }
+}
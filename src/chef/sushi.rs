@@ -0,0 +1,319 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::prelude::Utc;
+use ethers::prelude::{Address, U256};
+use futures::future::LocalBoxFuture;
+use serde::Serialize;
+
+use crate::apr;
+use crate::chef::{Chef, Context, FarmSource};
+use crate::constants;
+use crate::contracts;
+use crate::models;
+use crate::multicall;
+use crate::subgraph;
+
+/// Sushi's MiniChefV2 on Moonriver, paying SUSHI plus MOVR through its complex rewarder.
+pub struct SushiMiniChef {
+    pub chef: Chef,
+}
+
+impl FarmSource for SushiMiniChef {
+    fn chef(&self) -> &Chef {
+        &self.chef
+    }
+
+    fn prefetch<'a>(
+        &'a self,
+        pids: &'a [u32],
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+            let sushi_mini_chef = contracts::IMiniChefV2::new(
+                chef.address.parse::<Address>()?,
+                Arc::clone(&chef.client),
+            );
+            let mut calls = vec![];
+            for pid in pids {
+                calls.extend(multicall::key(&sushi_mini_chef.pool_info(U256::from(*pid))));
+                calls.extend(multicall::key(&sushi_mini_chef.lp_token(U256::from(*pid))));
+            }
+            chef.calls.prefetch(calls).await
+        })
+    }
+
+    fn fetch_farm<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+            let router = chef.router.clone();
+
+            let sushi_mini_chef_address = chef.address.parse::<Address>()?;
+            let sushi_mini_chef =
+                contracts::IMiniChefV2::new(sushi_mini_chef_address, Arc::clone(&chef.client));
+
+            // TODO: fetch this address from minichef contract
+            // right now hardcoding to prevent repeated calls (same rewarder is used for all pids)
+            let sushi_complex_rewarder_address =
+                constants::addresses::sushi_on_moonriver::SUSHI_COMPLEX_REWARDER
+                    .parse::<Address>()?;
+            let sushi_complex_rewarder = contracts::IComplexRewarderTime::new(
+                sushi_complex_rewarder_address,
+                Arc::clone(&chef.client),
+            );
+
+            let (_acc_native_reward_per_share, _last_reward_timestamp, alloc_point): (
+                u128,
+                u64,
+                u64,
+            ) = chef
+                .calls
+                .call(sushi_mini_chef.pool_info(ethers::prelude::U256::from(pid)))
+                .await?;
+
+            let ap = alloc_point as u32;
+
+            let mut underlying_assets: Vec<models::UnderlyingAsset> = vec![];
+            let farm_type = models::FarmType::StandardAmm;
+            let farm_implementation = chef.farm_implementation;
+
+            // if ap > 0 {
+            let lp_token: Address = chef
+                .calls
+                .call(sushi_mini_chef.lp_token(ethers::prelude::U256::from(pid)))
+                .await?;
+
+            let asset_addr = ethers::utils::to_checksum(&lp_token.to_owned(), None);
+            let asset = ctx
+                .store
+                .find_asset(&asset_addr, &chef.chain, &chef.protocol)
+                .await?;
+
+            let mut asset_price: f64;
+            let mut asset_tvl: f64 = 0.0;
+
+            let mut rewards = vec![];
+            // <symbol, (exists, amount, valueUSD, freq)>
+            let reward_asset_map: HashMap<String, (bool, f64, f64, String)> = HashMap::new();
+            let mut total_reward_apr = 0.0;
+
+            if asset.is_some() {
+                log::debug!("asset: {:?}", asset.clone().unwrap().symbol);
+                // asset.clone().unwrap().under
+                let lp = contracts::ILpToken::new(lp_token, Arc::clone(&chef.client));
+                chef.calls.call(lp.token_0()).await;
+                let sps: U256 = chef.calls.call(sushi_mini_chef.sushi_per_second()).await?;
+                let tap: U256 = chef.calls.call(sushi_mini_chef.total_alloc_point()).await?;
+                let rps: U256 = chef
+                    .calls
+                    .call(sushi_complex_rewarder.reward_per_second())
+                    .await?;
+                let sushi = ctx
+                    .store
+                    .find_asset(
+                        constants::addresses::sushi_on_moonriver::SUSHI,
+                        "moonriver",
+                        "sushiswap",
+                    )
+                    .await?;
+                let movr = ctx
+                    .store
+                    .find_asset(
+                        constants::addresses::sushi_on_moonriver::MOVR,
+                        "moonriver",
+                        "sushiswap",
+                    )
+                    .await?;
+
+                if sushi.is_some() || movr.is_some() {
+                    if sushi.is_some() {
+                        let reward_asset_price = sushi.clone().unwrap().price;
+                        log::debug!("reward_asset_price: {:?}", reward_asset_price);
+
+                        asset_price = asset.clone().unwrap().price;
+                        log::debug!("asset_price: {:?}", asset_price);
+
+                        let rewards_per_sec: f64 =
+                            sps.as_u128() as f64 * (ap as f64 / tap.as_u128() as f64);
+
+                        let rewards_per_day: f64 = rewards_per_sec * 60.0 * 60.0 * 24.0;
+                        asset_tvl = asset.clone().unwrap().liquidity;
+
+                        if rewards_per_day != 0.0 {
+                            rewards.push(models::Reward {
+                                amount: rewards_per_day as f64
+                                    / constants::utils::TEN_I128
+                                        .pow(sushi.clone().unwrap().decimals)
+                                        as f64,
+                                asset: sushi.clone().unwrap().symbol,
+                                value_usd: (rewards_per_day as f64
+                                    / constants::utils::TEN_I128
+                                        .pow(sushi.clone().unwrap().decimals)
+                                        as f64)
+                                    * reward_asset_price,
+                                freq: models::Freq::Daily.to_string(),
+                            });
+
+                            // reward_apr/farm_apr/pool_apr
+                            log::debug!(
+                                "rewards/sec: {} rewards/day: {} asset_tvl: {}",
+                                rewards_per_sec,
+                                rewards_per_day,
+                                asset_tvl
+                            );
+
+                            let reward_apr = ((rewards_per_day as f64 * reward_asset_price)
+                                / (asset_tvl as f64
+                                    * constants::utils::TEN_I128
+                                        .pow(sushi.clone().unwrap().decimals)
+                                        as f64))
+                                * 365.0
+                                * 100.0;
+                            log::debug!("reward_apr: {}", reward_apr);
+                            if asset_tvl != 0.0 && asset_price != 0.0 {
+                                total_reward_apr += reward_apr;
+                            }
+                        }
+                    }
+
+                    if movr.is_some() {
+                        let reward_asset_price = movr.clone().unwrap().price;
+                        log::debug!("reward_asset_price: {:?}", reward_asset_price);
+
+                        asset_price = asset.clone().unwrap().price;
+                        log::debug!("asset_price: {:?}", asset_price);
+
+                        let (
+                                _acc_native_reward_per_share,
+                                _last_reward_timestamp,
+                                r_alloc_point,
+                            ): (u128, u64, u64) = chef
+                                .calls
+                                .call(sushi_mini_chef.pool_info(ethers::prelude::U256::from(pid)))
+                                .await?;
+
+                        let rap = r_alloc_point as u32;
+
+                        let rewards_per_sec: f64 =
+                            rps.as_u128() as f64 * (rap as f64 / tap.as_u128() as f64);
+
+                        let rewards_per_day: f64 = rewards_per_sec * 60.0 * 60.0 * 24.0;
+                        asset_tvl = asset.clone().unwrap().liquidity;
+
+                        if rewards_per_day != 0.0 {
+                            rewards.push(models::Reward {
+                                amount: rewards_per_day as f64
+                                    / constants::utils::TEN_I128.pow(movr.clone().unwrap().decimals)
+                                        as f64,
+                                asset: movr.clone().unwrap().symbol,
+                                value_usd: (rewards_per_day as f64
+                                    / constants::utils::TEN_I128.pow(movr.clone().unwrap().decimals)
+                                        as f64)
+                                    * reward_asset_price,
+                                freq: models::Freq::Daily.to_string(),
+                            });
+
+                            // reward_apr/farm_apr/pool_apr
+                            log::debug!(
+                                "rewards/sec: {} rewards/day: {} asset_tvl: {}",
+                                rewards_per_sec,
+                                rewards_per_day,
+                                asset_tvl
+                            );
+
+                            let reward_apr = ((rewards_per_day as f64 * reward_asset_price)
+                                / (asset_tvl as f64
+                                    * constants::utils::TEN_I128.pow(movr.clone().unwrap().decimals)
+                                        as f64))
+                                * 365.0
+                                * 100.0;
+                            log::debug!("reward_apr: {}", reward_apr);
+                            if asset_tvl != 0.0 && asset_price != 0.0 {
+                                total_reward_apr += reward_apr;
+                            }
+                        }
+                    }
+
+                    // base_apr/trading_apr
+                    #[derive(Serialize)]
+                    pub struct Vars {
+                        addr: String,
+                        days: usize,
+                    }
+                    let vars = Vars {
+                        addr: asset.clone().unwrap().address.to_lowercase(),
+                        days: ctx.base_apr.days,
+                    };
+                    // the query skips today's day data, it only has part of a day's volume
+                    let pair_day_datas = chef
+                        .subgraph
+                        .query_with_vars_unwrap::<subgraph::SushiPairDayDatas, Vars>(
+                            &constants::chef::SUSHI_PAIR_DAY_DATAS_QUERY.clone(),
+                            vars,
+                        )
+                        .await;
+                    let daily_volumes: Vec<f64> = pair_day_datas
+                        .map(|pdd| pdd.pair_day_datas)
+                        .unwrap_or_default()
+                        .iter()
+                        .map(|pdd| pdd.volume_usd.parse().unwrap_or_default())
+                        .collect();
+                    let base_apr = apr::base_apr(
+                        &daily_volumes,
+                        asset.clone().unwrap_or_default().total_supply
+                            * asset.clone().unwrap_or_default().price,
+                        chef.lp_fee,
+                        ctx.base_apr,
+                    );
+
+                    let mut uas = vec![];
+                    for ua in asset.clone().unwrap().underlying_assets {
+                        uas.push(models::UnderlyingAsset {
+                            symbol: ua.symbol,
+                            address: ua.address,
+                            decimals: ua.decimals,
+                        })
+                    }
+
+                    let timestamp = Utc::now().to_string();
+
+                    log::debug!("chef v0 farm lastUpdatedAtUTC {}", timestamp.clone());
+
+                    let farm = models::Farm {
+                        id: pid as i32,
+                        chef: chef.address.clone(),
+                        router,
+                        chain: chef.chain.clone(),
+                        protocol: chef.protocol.clone(),
+                        farm_type: farm_type.to_string(),
+                        farm_impl: farm_implementation.to_string(),
+                        asset: models::FarmAsset {
+                            symbol: asset.clone().unwrap().symbol,
+                            address: asset_addr.clone(),
+                            price: asset.clone().unwrap().price,
+                            logos: asset.clone().unwrap().logos,
+                            underlying_assets: uas,
+                        },
+                        tvl: asset_tvl as f64,
+                        apr: models::APR {
+                            reward: total_reward_apr,
+                            base: base_apr,
+                        },
+                        rewards,
+                        alloc_point: ap,
+                        last_updated_at_utc: timestamp.clone(),
+                        block: chef.block,
+                        terms: chef.terms(),
+                        ..Default::default()
+                    };
+                    ctx.store.upsert_farm(&farm).await?;
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,642 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::prelude::Utc;
+use ethers::prelude::{Address, U256};
+use futures::future::LocalBoxFuture;
+use serde::Serialize;
+
+use crate::apr;
+use crate::chef::{Chef, Context, FarmSource};
+use crate::constants;
+use crate::contracts;
+use crate::lp;
+use crate::models;
+use crate::multicall;
+use crate::store;
+use crate::subsquid;
+
+/// Zenlink's `IFarming` chef, deployed on Moonriver, Moonbeam and Astar.
+pub struct Zenlink {
+    pub chef: Chef,
+}
+
+impl FarmSource for Zenlink {
+    fn chef(&self) -> &Chef {
+        &self.chef
+    }
+
+    fn prefetch<'a>(
+        &'a self,
+        pids: &'a [u32],
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+            let zenlink_chef = contracts::IFarming::new(
+                chef.address.parse::<Address>()?,
+                Arc::clone(&chef.client),
+            );
+            let calls = pids
+                .iter()
+                .filter_map(|pid| multicall::key(&zenlink_chef.get_pool_info(U256::from(*pid))))
+                .collect();
+            chef.calls.prefetch(calls).await
+        })
+    }
+
+    fn fetch_farm<'a>(
+        &'a self,
+        ctx: &'a Context,
+        pid: u32,
+    ) -> LocalBoxFuture<'a, Result<(), Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let chef = &self.chef;
+
+            let zenlink_chef_address = chef.address.parse::<Address>()?;
+            let zenlink_chef =
+                contracts::IFarming::new(zenlink_chef_address, Arc::clone(&chef.client));
+
+            let (
+                farming_token,
+                amount,
+                reward_tokens,
+                reward_per_block,
+                _acc_reward_per_share,
+                _last_reward_block,
+                _start_block,
+                claimable_interval,
+            ): (
+                Address,
+                U256,
+                Vec<Address>,
+                Vec<U256>,
+                Vec<U256>,
+                U256,
+                U256,
+                U256,
+            ) = chef
+                .calls
+                .call(zenlink_chef.get_pool_info(ethers::prelude::U256::from(pid)))
+                .await?;
+
+            let ft_addr = ethers::utils::to_checksum(&farming_token.to_owned(), None);
+
+            let mut underlying_assets: Vec<models::UnderlyingAsset> = vec![];
+            let mut farm_type = models::FarmType::StandardAmm;
+
+            let mut router = chef.router.clone();
+
+            let asset = ctx
+                .store
+                .find_asset(&ft_addr, &chef.chain, &chef.protocol)
+                .await?;
+
+            if asset.is_some() {
+                for ua in asset.clone().unwrap().underlying_assets {
+                    underlying_assets.push(models::UnderlyingAsset {
+                        symbol: ua.symbol,
+                        address: ua.address,
+                        decimals: ua.decimals,
+                    })
+                }
+            }
+
+            if pid == 3 && chef.chain.clone() == "astar".to_string() {
+                farm_type = models::FarmType::StableAmm;
+
+                let stable_asset =
+                    contracts::IStableLpToken::new(farming_token, Arc::clone(&chef.client));
+
+                let owner_addr: Address = chef.calls.call(stable_asset.owner()).await?;
+                let stable_owner_addr = ethers::utils::to_checksum(&owner_addr.to_owned(), None);
+                router = stable_owner_addr.clone();
+
+                let owner =
+                    contracts::IStableLpTokenOwner::new(owner_addr, Arc::clone(&chef.client));
+                let stable_lp_underlying_tokens: Vec<Address> =
+                    chef.calls.call(owner.get_tokens()).await?;
+                let stable_lp_underlying_balances =
+                    chef.calls.call(owner.get_token_balances()).await?;
+                log::debug!(
+                    "stable_lp_underlying_tokens: {:#?}",
+                    stable_lp_underlying_tokens
+                );
+                log::debug!(
+                    "stable_lp_underlying_balances: {:#?}",
+                    stable_lp_underlying_balances
+                );
+
+                let stable_lp = lp::stable_lp_price(
+                    &chef.calls,
+                    ctx.store.as_ref(),
+                    &chef.chain,
+                    &chef.protocol,
+                    farming_token,
+                )
+                .await?;
+                log::debug!("zenlink 4pool stable_lp {:?}", stable_lp);
+
+                let bai_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::zenlink_on_astar::BAI,
+                    &chef.chain,
+                    &chef.protocol,
+                )
+                .await?;
+                let busd_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::zenlink_on_astar::BUSD,
+                    &chef.chain,
+                    &chef.protocol,
+                )
+                .await?;
+                let dai_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::zenlink_on_astar::DAI,
+                    &chef.chain,
+                    &chef.protocol,
+                )
+                .await?;
+                let usdc_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::zenlink_on_astar::USDC,
+                    &chef.chain,
+                    &chef.protocol,
+                )
+                .await?;
+
+                underlying_assets = vec![
+                    models::UnderlyingAsset {
+                        symbol: usdc_asset.clone().symbol,
+                        address: usdc_asset.clone().address,
+                        decimals: usdc_asset.clone().decimals,
+                    },
+                    models::UnderlyingAsset {
+                        symbol: busd_asset.clone().symbol,
+                        address: busd_asset.clone().address,
+                        decimals: busd_asset.clone().decimals,
+                    },
+                    models::UnderlyingAsset {
+                        symbol: bai_asset.clone().symbol,
+                        address: bai_asset.clone().address,
+                        decimals: bai_asset.clone().decimals,
+                    },
+                    models::UnderlyingAsset {
+                        symbol: dai_asset.clone().symbol,
+                        address: dai_asset.clone().address,
+                        decimals: dai_asset.clone().decimals,
+                    },
+                ];
+
+                let usd_pool_liq = stable_lp.liquidity;
+                let ts = stable_lp.total_supply;
+                let usd_pool_price = stable_lp.price;
+                log::debug!("usd_pool_price {}", usd_pool_price);
+
+                let timestamp = Utc::now().to_string();
+
+                let asset = models::Asset {
+                    address: ft_addr.to_string(),
+                    chain: chef.chain.clone(),
+                    protocol: chef.protocol.clone(),
+                    name: "Zenlink Stable AMM 4pool LP".to_string(),
+                    symbol: "4pool".to_string(),
+                    decimals: 18,
+                    logos: vec![
+                        usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        busd_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        dai_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        bai_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                    ],
+                    price: usd_pool_price,
+                    price_source: models::PriceSource::DexReserves,
+                    price_confidence: models::PriceConfidence::High,
+                    peg: None,
+                    liquidity: usd_pool_liq,
+                    total_supply: ts,
+                    is_lp: true,
+                    fees_apr: 0.0,
+                    underlying_assets: underlying_assets.clone(),
+                    underlying_assets_alloc: vec![],
+                    last_updated_at_utc: timestamp.clone(),
+                };
+
+                ctx.store.upsert_asset(&asset).await?;
+            } else if pid == 11 && chef.chain.clone() == "moonriver".to_string() {
+                farm_type = models::FarmType::StableAmm;
+
+                let stable_asset =
+                    contracts::IStableLpToken::new(farming_token, Arc::clone(&chef.client));
+
+                let owner_addr: Address = chef.calls.call(stable_asset.owner()).await?;
+                let stable_owner_addr = ethers::utils::to_checksum(&owner_addr.to_owned(), None);
+                router = stable_owner_addr.clone();
+
+                let owner =
+                    contracts::IStableLpTokenOwner::new(owner_addr, Arc::clone(&chef.client));
+                let stable_lp_underlying_tokens: Vec<Address> =
+                    chef.calls.call(owner.get_tokens()).await?;
+                let stable_lp_underlying_balances =
+                    chef.calls.call(owner.get_token_balances()).await?;
+                log::debug!(
+                    "stable_lp_underlying_tokens: {:#?}",
+                    stable_lp_underlying_tokens
+                );
+                log::debug!(
+                    "stable_lp_underlying_balances: {:#?}",
+                    stable_lp_underlying_balances
+                );
+
+                let stable_lp = lp::stable_lp_price(
+                    &chef.calls,
+                    ctx.store.as_ref(),
+                    &chef.chain,
+                    &chef.protocol,
+                    farming_token,
+                )
+                .await?;
+                log::debug!("zenlink 4pool stable_lp {:?}", stable_lp);
+
+                let usdt_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::USDT,
+                    &chef.chain,
+                    "solarbeam",
+                )
+                .await?;
+                let frax_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::solarbeam_on_moonriver::FRAX,
+                    &chef.chain,
+                    "solarbeam",
+                )
+                .await?;
+                let usdc_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::zenlink_on_moonriver::USDC,
+                    &chef.chain,
+                    &chef.protocol,
+                )
+                .await?;
+                let xcausd_asset = store::required_asset(
+                    ctx.store.as_ref(),
+                    constants::addresses::zenlink_on_moonriver::XCAUSD,
+                    &chef.chain,
+                    &chef.protocol,
+                )
+                .await?;
+
+                underlying_assets = vec![
+                    models::UnderlyingAsset {
+                        symbol: usdt_asset.clone().symbol,
+                        address: usdt_asset.clone().address,
+                        decimals: usdt_asset.clone().decimals,
+                    },
+                    models::UnderlyingAsset {
+                        symbol: usdc_asset.clone().symbol,
+                        address: usdc_asset.clone().address,
+                        decimals: usdc_asset.clone().decimals,
+                    },
+                    models::UnderlyingAsset {
+                        symbol: xcausd_asset.clone().symbol,
+                        address: xcausd_asset.clone().address,
+                        decimals: xcausd_asset.clone().decimals,
+                    },
+                    models::UnderlyingAsset {
+                        symbol: frax_asset.clone().symbol,
+                        address: frax_asset.clone().address,
+                        decimals: frax_asset.clone().decimals,
+                    },
+                ];
+
+                let usd_pool_liq = stable_lp.liquidity;
+                let ts = stable_lp.total_supply;
+                let usd_pool_price = stable_lp.price;
+                log::debug!("usd_pool_price {}", usd_pool_price);
+
+                let timestamp = Utc::now().to_string();
+
+                let asset = models::Asset {
+                    address: ft_addr.to_string(),
+                    chain: chef.chain.clone(),
+                    protocol: chef.protocol.clone(),
+                    name: "Zenlink Stable AMM 4pool LP".to_string(),
+                    symbol: "4pool".to_string(),
+                    decimals: 18,
+                    logos: vec![
+                        usdt_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        usdc_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                        xcausd_asset
+                            .clone()
+                            .logos
+                            .get(0)
+                            .cloned()
+                            .unwrap_or_default(),
+                        frax_asset.clone().logos.get(0).cloned().unwrap_or_default(),
+                    ],
+                    price: usd_pool_price,
+                    price_source: models::PriceSource::DexReserves,
+                    price_confidence: models::PriceConfidence::High,
+                    peg: None,
+                    liquidity: usd_pool_liq,
+                    total_supply: ts,
+                    is_lp: true,
+                    fees_apr: 0.0,
+                    underlying_assets: underlying_assets.clone(),
+                    underlying_assets_alloc: vec![],
+                    last_updated_at_utc: timestamp.clone(),
+                };
+
+                ctx.store.upsert_asset(&asset).await?;
+            } else if pid == 1 && chef.chain.clone() == "moonriver".to_string() {
+                // zlk on moonriver
+                farm_type = models::FarmType::SingleStaking;
+                underlying_assets = vec![models::UnderlyingAsset {
+                    symbol: "ZLK".to_string(),
+                    address: constants::addresses::zenlink_on_moonriver::ZLK.to_string(),
+                    decimals: 18,
+                }];
+            } else if pid == 1 && chef.chain.clone() == "moonbeam".to_string() {
+                // zlk on moonbeam
+                farm_type = models::FarmType::SingleStaking;
+                underlying_assets = vec![models::UnderlyingAsset {
+                    symbol: "ZLK".to_string(),
+                    address: constants::addresses::zenlink_on_moonbeam::ZLK.to_string(),
+                    decimals: 18,
+                }];
+            }
+
+            let mut asset_price: f64 = 0.0;
+            let mut asset_tvl: u128 = 0;
+
+            let mut rewards = vec![];
+            // <symbol, (exists, amount, valueUSD, freq)>
+            let mut reward_asset_map: HashMap<String, (bool, f64, f64, String)> = HashMap::new();
+            let mut total_reward_apr = 0.0;
+
+            if asset.is_some() {
+                log::debug!(
+                    "--------------------\nasset: {:?}",
+                    asset.clone().unwrap().symbol
+                );
+
+                for i in 0..reward_tokens.len() {
+                    let reward_asset_addr =
+                        ethers::utils::to_checksum(&reward_tokens[i].to_owned(), None);
+                    log::debug!("reward_asset_addr: {:?}", reward_asset_addr);
+                    let reward_asset = ctx
+                        .store
+                        .find_asset(&reward_asset_addr, &chef.chain, &chef.protocol)
+                        .await?;
+
+                    if reward_asset.is_some() {
+                        let reward_asset_price = reward_asset.clone().unwrap().price;
+                        log::debug!("reward_asset_price: {:?}", reward_asset_price);
+
+                        asset_price = asset.clone().unwrap().price;
+
+                        log::debug!("asset_price: {:?}", asset_price);
+
+                        let rpb = reward_per_block[i].as_u128();
+                        let rewards_per_sec: f64 = rpb as f64 / chef.block_time;
+                        let rewards_per_day: u128 = rewards_per_sec as u128 * 60 * 60 * 24;
+                        log::debug!(
+                            "rpb {:?} rewards_per_sec {:?} rewards_per_day {:?}",
+                            rpb,
+                            rewards_per_sec,
+                            rewards_per_day
+                        );
+
+                        asset_tvl = amount.as_u128();
+
+                        if rewards_per_day != 0 {
+                            if !reward_asset_map.contains_key(&reward_asset.clone().unwrap().symbol)
+                            {
+                                reward_asset_map.insert(
+                                    reward_asset.clone().unwrap().symbol,
+                                    (
+                                        true,
+                                        rewards_per_day as f64
+                                            / constants::utils::TEN_I128
+                                                .pow(reward_asset.clone().unwrap().decimals)
+                                                as f64,
+                                        (rewards_per_day as f64
+                                            / constants::utils::TEN_I128
+                                                .pow(reward_asset.clone().unwrap().decimals)
+                                                as f64)
+                                            * reward_asset_price,
+                                        models::Freq::Daily.to_string(),
+                                    ),
+                                );
+                            } else {
+                                let er = reward_asset_map
+                                    .get(&reward_asset.clone().unwrap().symbol)
+                                    .unwrap();
+                                reward_asset_map.insert(
+                                    reward_asset.clone().unwrap().symbol,
+                                    (
+                                        true,
+                                        er.1 + rewards_per_day as f64
+                                            / constants::utils::TEN_I128
+                                                .pow(reward_asset.clone().unwrap().decimals)
+                                                as f64,
+                                        er.2 + (rewards_per_day as f64
+                                            / constants::utils::TEN_I128
+                                                .pow(reward_asset.clone().unwrap().decimals)
+                                                as f64)
+                                            * reward_asset_price,
+                                        models::Freq::Daily.to_string(),
+                                    ),
+                                );
+                            }
+
+                            // reward_apr/farm_apr/pool_apr
+                            log::debug!(
+                                "rewards/sec: {} rewards/day: {} asset_tvl: {}",
+                                rewards_per_sec,
+                                rewards_per_day,
+                                asset_tvl
+                            );
+
+                            let mut reward_apr = ((rewards_per_day as f64
+                                / constants::utils::TEN_I128
+                                    .pow(reward_asset.clone().unwrap().decimals)
+                                    as f64
+                                * reward_asset_price)
+                                / (asset_tvl as f64 * asset_price))
+                                * 365.0
+                                * 100.0;
+                            if farm_type == models::FarmType::SingleStaking
+                                || farm_type == models::FarmType::StableAmm
+                            {
+                                reward_apr *= constants::utils::TEN_I128.pow(18) as f64;
+                            }
+
+                            log::debug!("reward_apr: {}", reward_apr);
+                            if asset_tvl != 0 && asset_price != 0.0 {
+                                total_reward_apr += reward_apr;
+                            }
+                        }
+                    }
+                }
+
+                for r in reward_asset_map.iter() {
+                    rewards.push(models::Reward {
+                        amount: r.1 .1,
+                        asset: r.0.clone(),
+                        value_usd: r.1 .2,
+                        freq: models::Freq::Daily.to_string(),
+                    });
+                }
+
+                let mut atvl: f64 = asset_tvl as f64 * asset_price;
+                if farm_type == models::FarmType::SingleStaking
+                    || farm_type == models::FarmType::StableAmm
+                {
+                    atvl = asset_tvl as f64 * asset_price / constants::utils::TEN_F64.powf(18.0);
+                }
+
+                log::debug!(
+                    "rewards {:?} total_reward_apr {:?} tvl {:?}",
+                    rewards.clone(),
+                    total_reward_apr,
+                    atvl
+                );
+                log::debug!("--------------------\n");
+
+                if rewards.len() > 0 {
+                    // base_apr/trading_apr
+                    #[derive(Serialize)]
+                    pub struct Vars {
+                        addr: String,
+                        days: usize,
+                    }
+                    let vars = Vars {
+                        addr: asset.clone().unwrap().address.to_lowercase(),
+                        days: ctx.base_apr.days,
+                    };
+                    let liquidity = asset.clone().unwrap_or_default().total_supply
+                        * asset.clone().unwrap_or_default().price;
+                    let pair_day_datas = chef
+                        .subgraph
+                        .query_with_vars_unwrap::<subsquid::ZenlinkPairDayDatas, Vars>(
+                            &constants::subsquid::PAIR_DAY_DATAS_QUERY.clone(),
+                            vars,
+                        )
+                        .await;
+                    let daily_volumes: Vec<f64> = match pair_day_datas {
+                        Ok(pair_day_datas) => pair_day_datas
+                            .pair_day_data
+                            .iter()
+                            .map(|pdd| pdd.daily_volume_usd.parse().unwrap_or_default())
+                            .collect(),
+                        Err(e) => {
+                            log::warn!("couldn't fetch zenlink pid {} pair day datas: {}", pid, e);
+                            vec![]
+                        }
+                    };
+                    let mut base_apr =
+                        apr::base_apr(&daily_volumes, liquidity, chef.lp_fee, ctx.base_apr);
+                    // if stable
+                    if (pid == 11 && chef.chain.clone() == "moonriver".to_string())
+                        || (pid == 3 && chef.chain.clone() == "astar".to_string())
+                    {
+                        #[derive(Serialize)]
+                        pub struct StableVars {
+                            days: usize,
+                        }
+                        let zenlink_stable_swaps = chef
+                            .subgraph
+                            .query_with_vars_unwrap::<subsquid::ZenlinkStableSwaps, StableVars>(
+                                &constants::subsquid::STABLE_SWAPS_DAY_DATA_QUERY.clone(),
+                                StableVars {
+                                    days: ctx.base_apr.days,
+                                },
+                            )
+                            .await;
+
+                        if pid == 11 && chef.chain.clone() == "moonriver".to_string() {
+                            router = "0xE04B18eFF27B55A3BB7E4451C0829Daf594843fD".to_string();
+                        } else if pid == 3 && chef.chain.clone() == "astar".to_string() {
+                            router = "0x7F12564eca712fa59b0EEdfE56EABC8b53a7B0cd".to_string();
+                        }
+
+                        match zenlink_stable_swaps {
+                            Ok(zenlink_stable_swaps) => {
+                                let daily_volumes: Vec<f64> = zenlink_stable_swaps
+                                    .stable_swaps
+                                    .get(0)
+                                    .map(|ss| {
+                                        ss.stable_swap_day_data
+                                            .iter()
+                                            .map(|dd| {
+                                                dd.daily_volume_usd.parse().unwrap_or_default()
+                                            })
+                                            .collect()
+                                    })
+                                    .unwrap_or_default();
+                                base_apr = apr::base_apr(
+                                    &daily_volumes,
+                                    liquidity,
+                                    chef.stable_lp_fee.ok_or_else(|| {
+                                        format!("no stable fees configured for {}", chef.protocol)
+                                    })?,
+                                    ctx.base_apr,
+                                );
+                            }
+                            Err(e) => log::warn!(
+                                "couldn't fetch zenlink pid {} stable swap day datas: {}",
+                                pid,
+                                e
+                            ),
+                        }
+                    }
+
+                    let timestamp = Utc::now().to_string();
+
+                    // if pid != 12 && chef.chain.clone() == "moonriver".to_string() {
+                    log::debug!(
+                        "zenlink chef v3 farm lastUpdatedAtUTC {}",
+                        timestamp.clone()
+                    );
+
+                    let farm = models::Farm {
+                        id: pid as i32,
+                        chef: chef.address.clone(),
+                        router,
+                        chain: chef.chain.clone(),
+                        protocol: "zenlink".to_string(),
+                        farm_type: farm_type.to_string(),
+                        farm_impl: chef.farm_implementation.to_string(),
+                        asset: models::FarmAsset {
+                            symbol: asset.clone().unwrap().symbol,
+                            address: asset.clone().unwrap().address,
+                            price: asset.clone().unwrap().price,
+                            logos: asset.clone().unwrap().logos,
+                            underlying_assets: underlying_assets.clone(),
+                        },
+                        tvl: atvl,
+                        apr: models::APR {
+                            reward: total_reward_apr,
+                            base: base_apr,
+                        },
+                        rewards,
+                        alloc_point: 1,
+                        last_updated_at_utc: timestamp.clone(),
+                        block: chef.block,
+                        // the farming contract's claimable interval is in blocks
+                        terms: models::FarmTerms {
+                            harvest_interval: (claimable_interval.as_u64() as f64 * chef.block_time)
+                                as u64,
+                            ..chef.terms()
+                        },
+                        ..Default::default()
+                    };
+                    ctx.store.upsert_farm(&farm).await?;
+                    // }
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
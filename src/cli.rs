@@ -0,0 +1,75 @@
+use clap::{Parser, Subcommand, ValueEnum};
+
+/// Seconds between runs when looping.
+pub const DEFAULT_INTERVAL: u64 = 60 * 3;
+
+#[derive(Debug, Parser)]
+#[clap(name = "bay-watcher", about = "The primary ETL service for Yield Bay.")]
+pub struct Cli {
+    /// Mongo database to write to, overrides `DB_NAME`.
+    #[clap(long, global = true)]
+    pub db_name: Option<String>,
+
+    /// Protocol registry to load, overrides `CONFIG_PATH`.
+    #[clap(long, global = true)]
+    pub config: Option<String>,
+
+    /// Scoring methodology to load, overrides `SCORING_CONFIG_PATH`.
+    #[clap(long, global = true)]
+    pub scoring_config: Option<String>,
+
+    /// Write assets and farms to memory instead of mongo and print them after the run.
+    #[clap(long, global = true)]
+    pub dry_run: bool,
+
+    /// With `--dry-run`, write the documents to this json file instead of printing them.
+    #[clap(long, global = true)]
+    pub dump: Option<String>,
+
+    /// env_logger filter, e.g. `info` or `ethers=debug`.
+    #[clap(long, global = true, default_value = "info")]
+    pub log_level: String,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Run every job in a loop (the default).
+    Run {
+        /// Seconds to wait between runs.
+        #[clap(long, default_value_t = DEFAULT_INTERVAL)]
+        interval: u64,
+    },
+    /// Run every job once and exit.
+    Once,
+    /// Run a single job once and exit.
+    Job {
+        #[clap(value_enum)]
+        name: Job,
+        /// Only run the subgraphs/chefs of this protocol.
+        #[clap(long)]
+        protocol: Option<String>,
+        /// Only run the subgraphs/chefs on this chain.
+        #[clap(long)]
+        chain: Option<String>,
+    },
+    /// Only compute safety scores.
+    Score,
+    /// Print the chains, subgraphs and chefs in the protocol registry.
+    ListProtocols,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum Job {
+    Pulsar,
+    Demeter,
+    Curve,
+    TapioTaiga,
+    Subgraph,
+    Chef,
+    Yields,
+    Score,
+    Snapshot,
+}
@@ -0,0 +1,724 @@
+use std::{collections::HashMap, fs};
+
+use serde::Deserialize;
+
+use crate::apr;
+use crate::models;
+use crate::multicall;
+
+/// Path used when `CONFIG_PATH` isn't set.
+pub const DEFAULT_CONFIG_PATH: &str = "config/protocols.toml";
+
+/// Path used when `SCORING_CONFIG_PATH` isn't set.
+pub const DEFAULT_SCORING_CONFIG_PATH: &str = "config/scoring.toml";
+
+/// Registry of chains, dex subgraphs and chef contracts the watcher runs against.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Config {
+    pub chains: Vec<Chain>,
+    #[serde(default)]
+    pub subgraphs: Vec<Subgraph>,
+    #[serde(default)]
+    pub chefs: Vec<Chef>,
+    #[serde(default)]
+    pub snapshots: Snapshots,
+    #[serde(default)]
+    pub prices: Vec<PriceFeed>,
+    /// Days of volume chef base APRs are averaged over.
+    #[serde(default)]
+    pub base_apr: apr::Window,
+    #[serde(default)]
+    pub fees: Vec<Fees>,
+    #[serde(default)]
+    pub yields: Yields,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chain {
+    pub name: String,
+    /// Name of the env var holding the RPC url, so keyed urls stay out of the config.
+    pub rpc_url_env: String,
+    /// Average block time in seconds.
+    pub block_time: f64,
+    /// Max pids fetched at once from a chef on this chain.
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize,
+    /// Blocks subgraph (blocklytics) used to find the block at a timestamp, which is
+    /// binary searched over rpc without one.
+    pub blocks_subgraph: Option<String>,
+    /// Multicall3 the chefs' view calls are batched through, empty to make every call
+    /// on its own.
+    #[serde(default = "default_multicall")]
+    pub multicall: String,
+}
+
+fn default_concurrency() -> usize {
+    4
+}
+
+fn default_multicall() -> String {
+    multicall::MULTICALL3.to_string()
+}
+
+/// How long farm and asset snapshots are kept before a run prunes them.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Snapshots {
+    #[serde(default = "default_retention_days")]
+    pub farm_retention_days: u32,
+    #[serde(default = "default_retention_days")]
+    pub asset_retention_days: u32,
+}
+
+impl Default for Snapshots {
+    fn default() -> Self {
+        Snapshots {
+            farm_retention_days: default_retention_days(),
+            asset_retention_days: default_retention_days(),
+        }
+    }
+}
+
+fn default_retention_days() -> u32 {
+    90
+}
+
+/// How a farm's APY and net APR are derived from its APRs, see `models::Yields`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct Yields {
+    #[serde(default)]
+    pub compounding: models::Compounding,
+    /// Days a deposit is assumed to be held for, its deposit and withdrawal fees are
+    /// spread over them.
+    #[serde(default = "default_holding_days")]
+    pub holding_days: u32,
+}
+
+impl Default for Yields {
+    fn default() -> Self {
+        Yields {
+            compounding: models::Compounding::default(),
+            holding_days: default_holding_days(),
+        }
+    }
+}
+
+fn default_holding_days() -> u32 {
+    365
+}
+
+/// Where the price oracle looks up a token's price besides the dex subgraphs. Matched
+/// on `address`, or on `symbol` when the feed has no address.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceFeed {
+    pub chain: String,
+    pub address: Option<String>,
+    pub symbol: Option<String>,
+    /// Sources to try, in order. Defaults to `oracle::DEFAULT_SOURCES`.
+    pub sources: Option<Vec<models::PriceSource>>,
+    /// Coingecko id, e.g. `lido-dao`.
+    pub coingecko: Option<String>,
+    /// GeckoTerminal pool url, the token has to be the pool's base token.
+    pub geckoterminal: Option<String>,
+    /// DexScreener pair url, the token has to be the pair's base token.
+    pub dexscreener: Option<String>,
+    /// A v2 pair the token is priced in against the pair's other token.
+    pub pair: Option<String>,
+    /// Usd price a stable is pegged to. Stables are priced at their peg unless the
+    /// subgraph/reserves price is off by more than `depeg_threshold`.
+    pub peg: Option<f64>,
+    /// Relative deviation from the peg that counts as a depeg.
+    #[serde(default = "default_depeg_threshold")]
+    pub depeg_threshold: f64,
+}
+
+fn default_depeg_threshold() -> f64 {
+    0.02
+}
+
+/// The kinds of pools a protocol charges fees on.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolKind {
+    /// Uniswap v2 style pairs.
+    Amm,
+    /// Stable-swap (saddle style) pools.
+    Stable,
+}
+
+fn default_pool_kind() -> PoolKind {
+    PoolKind::Amm
+}
+
+/// Swap fees of a protocol's pools of one kind, which fee and base APRs are computed from.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fees {
+    pub protocol: String,
+    #[serde(default = "default_pool_kind")]
+    pub pool: PoolKind,
+    /// Fee charged on each swap, e.g. 0.003 for 0.3%.
+    pub swap_fee: f64,
+    /// The part of `swap_fee` paid to LPs, the rest going to the protocol. Defaults to all
+    /// of it.
+    pub lp_fee: Option<f64>,
+    /// Fraction of the swap fee a stable pool's admin keeps (the pool's `adminFee`).
+    #[serde(default)]
+    pub admin_fee: f64,
+}
+
+impl Fees {
+    /// Share of each swap's volume that LPs earn.
+    pub fn lp_rate(&self) -> f64 {
+        self.lp_fee.unwrap_or(self.swap_fee) * (1.0 - self.admin_fee)
+    }
+}
+
+/// A dex subgraph/subsquid whose tokens and pairs are indexed into assets.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Subgraph {
+    pub protocol: String,
+    pub chain: String,
+    pub url: String,
+}
+
+/// The chef implementations farms can be read with.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Adapter {
+    Arthswap,
+    Zenlink,
+    SushiMiniChef,
+    Beamswap,
+    StellaswapV1,
+    StellaswapV2,
+    Solarbeam,
+    Solarflare,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Chef {
+    pub adapter: Adapter,
+    pub protocol: String,
+    pub chain: String,
+    pub version: String,
+    pub address: String,
+    pub router: String,
+    pub subgraph: String,
+    pub stable_subgraph: Option<String>,
+    #[serde(default = "default_farm_implementation")]
+    pub farm_implementation: models::FarmImplementation,
+    /// Listed pools. When unset every pid up to the chef's `poolLength` is fetched.
+    pub pids: Option<Vec<u32>>,
+    /// Seconds the chef's harvested rewards vest over, when they're an `IVestedToken` rather
+    /// than paid out at once. Their reward APR is left out of APY compounding.
+    pub reward_vesting: Option<u64>,
+}
+
+fn default_farm_implementation() -> models::FarmImplementation {
+    models::FarmImplementation::Solidity
+}
+
+impl Config {
+    pub fn chain(&self, name: &str) -> Result<&Chain, Box<dyn std::error::Error>> {
+        self.chains
+            .iter()
+            .find(|c| c.name == name)
+            .ok_or_else(|| format!("chain {} is not configured", name).into())
+    }
+
+    /// Share of each swap LPs earn in `protocol`'s pools of kind `pool`.
+    pub fn lp_fee(
+        &self,
+        protocol: &str,
+        pool: PoolKind,
+    ) -> Result<f64, Box<dyn std::error::Error>> {
+        self.fees
+            .iter()
+            .find(|f| f.protocol == protocol && f.pool == pool)
+            .map(|f| f.lp_rate())
+            .ok_or_else(|| format!("no {:?} fees configured for {}", pool, protocol).into())
+    }
+
+    /// Keeps only the subgraphs and chefs matching `protocol` and `chain`, when given.
+    pub fn filter(&self, protocol: Option<&str>, chain: Option<&str>) -> Config {
+        let matches = |p: &str, c: &str| {
+            protocol.map_or(true, |protocol| protocol == p)
+                && chain.map_or(true, |chain| chain == c)
+        };
+
+        Config {
+            chains: self.chains.clone(),
+            subgraphs: self
+                .subgraphs
+                .iter()
+                .filter(|s| matches(&s.protocol, &s.chain))
+                .cloned()
+                .collect(),
+            chefs: self
+                .chefs
+                .iter()
+                .filter(|c| matches(&c.protocol, &c.chain))
+                .cloned()
+                .collect(),
+            snapshots: self.snapshots.clone(),
+            prices: self.prices.clone(),
+            base_apr: self.base_apr,
+            fees: self.fees.clone(),
+            yields: self.yields,
+        }
+    }
+}
+
+/// Reads the registry from `CONFIG_PATH`, falling back to `DEFAULT_CONFIG_PATH`.
+pub fn load() -> Result<Config, Box<dyn std::error::Error>> {
+    let path = dotenv::var("CONFIG_PATH").unwrap_or_else(|_| DEFAULT_CONFIG_PATH.to_string());
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("couldn't read {}: {}", path, e))?;
+    let config: Config = toml::from_str(&contents)?;
+
+    for chef in config.chefs.iter() {
+        config.chain(&chef.chain)?;
+        if matches!(chef.adapter, Adapter::StellaswapV2 | Adapter::Solarbeam)
+            && chef.stable_subgraph.is_none()
+        {
+            return Err(format!(
+                "{} {} on {} needs a stable_subgraph",
+                chef.protocol, chef.version, chef.chain
+            )
+            .into());
+        }
+    }
+
+    if config.yields.holding_days == 0 {
+        return Err("yields need a holding period of at least a day".into());
+    }
+    if config.base_apr.days == 0 {
+        return Err("base_apr needs at least a day of volume".into());
+    }
+    if let apr::Averaging::Exponential { half_life } = config.base_apr.averaging {
+        if half_life <= 0.0 {
+            return Err("base_apr half_life has to be positive".into());
+        }
+    }
+
+    for fees in config.fees.iter() {
+        if fees.lp_fee.map_or(false, |lp_fee| lp_fee > fees.swap_fee)
+            || !(0.0..=1.0).contains(&fees.admin_fee)
+        {
+            return Err(format!("{:?} fees of {} don't add up", fees.pool, fees.protocol).into());
+        }
+    }
+    for protocol in config
+        .chefs
+        .iter()
+        .map(|c| &c.protocol)
+        .chain(config.subgraphs.iter().map(|s| &s.protocol))
+    {
+        config.lp_fee(protocol, PoolKind::Amm)?;
+    }
+
+    for feed in config.prices.iter() {
+        config.chain(&feed.chain)?;
+        if feed.address.is_none() && feed.symbol.is_none() {
+            return Err(
+                format!("price feed on {} needs an address or a symbol", feed.chain).into(),
+            );
+        }
+    }
+
+    Ok(config)
+}
+
+/// Safety score methodology, read anew by every scoring run so it can be changed
+/// without a deploy. `version` is stored on every scored farm.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scoring {
+    pub version: String,
+    pub weights: ScoreWeights,
+    /// Score of a farm by its tvl, from the highest bucket it reaches. Farms below every
+    /// bucket score 0.
+    pub tvl_buckets: Vec<TvlBucket>,
+    /// Fixed base APR scores by farm type, e.g. `StableAmm`. Other farms are scored by
+    /// their base APR relative to the highest one.
+    #[serde(default)]
+    pub farm_type_base_apr_scores: HashMap<String, f64>,
+    /// Taken off farms whose rewards or deposits are held for a month or more, less for
+    /// shorter holds.
+    #[serde(default)]
+    pub lockup_penalty: f64,
+    /// Which farms get scored, see `Scoring::rule`.
+    #[serde(default)]
+    pub rules: Vec<ScoringRule>,
+    #[serde(default)]
+    pub risk: RiskParams,
+    /// Impermanent loss exposure scores by farm type, e.g. `StandardAmm`.
+    #[serde(default)]
+    pub impermanent_loss_scores: HashMap<String, f64>,
+    /// Scores by farm implementation, e.g. `Solidity`.
+    #[serde(default)]
+    pub implementation_scores: HashMap<String, f64>,
+    /// Registry of protocol audits. Protocols missing from it are `unknown`.
+    #[serde(default)]
+    pub audits: Vec<Audit>,
+    #[serde(default)]
+    pub audit_scores: AuditScores,
+}
+
+impl Scoring {
+    /// The first rule matching the farm, which decides whether it's scored. Farms no rule
+    /// matches are scored.
+    pub fn rule(&self, farm: &models::Farm) -> Option<&ScoringRule> {
+        self.rules.iter().find(|r| r.matches(farm))
+    }
+
+    pub fn audit_status(&self, protocol: &str) -> AuditStatus {
+        self.audits
+            .iter()
+            .find(|a| a.protocol == protocol)
+            .map_or(AuditStatus::Unknown, |a| a.status)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Include,
+    Exclude,
+}
+
+/// Includes or excludes the farms matching all of its set fields, e.g. the `ids` of a
+/// `chef`, for `reason`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScoringRule {
+    pub action: RuleAction,
+    pub reason: String,
+    pub protocol: Option<String>,
+    pub chain: Option<String>,
+    pub chef: Option<String>,
+    pub ids: Option<Vec<i32>>,
+    pub symbols: Option<Vec<String>>,
+    /// Matches farms without (or with) allocation points.
+    pub inactive: Option<bool>,
+}
+
+impl ScoringRule {
+    pub fn matches(&self, farm: &models::Farm) -> bool {
+        self.protocol.as_ref().map_or(true, |p| *p == farm.protocol)
+            && self.chain.as_ref().map_or(true, |c| *c == farm.chain)
+            && self
+                .chef
+                .as_ref()
+                .map_or(true, |c| c.eq_ignore_ascii_case(&farm.chef))
+            && self.ids.as_ref().map_or(true, |ids| ids.contains(&farm.id))
+            && self
+                .symbols
+                .as_ref()
+                .map_or(true, |s| s.contains(&farm.asset.symbol))
+            && self
+                .inactive
+                .map_or(true, |inactive| inactive == (farm.alloc_point == 0))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ScoreWeights {
+    pub tvl: f64,
+    pub base_apr: f64,
+    pub reward_apr: f64,
+    pub rewards_usd: f64,
+    // risk factors, see `risk::RiskScores`
+    #[serde(default)]
+    pub age: f64,
+    #[serde(default)]
+    pub tvl_trend: f64,
+    #[serde(default)]
+    pub reward_volatility: f64,
+    #[serde(default)]
+    pub peg: f64,
+    #[serde(default)]
+    pub impermanent_loss: f64,
+    #[serde(default)]
+    pub audit: f64,
+    #[serde(default)]
+    pub implementation: f64,
+}
+
+impl ScoreWeights {
+    fn all(&self) -> [f64; 11] {
+        [
+            self.tvl,
+            self.base_apr,
+            self.reward_apr,
+            self.rewards_usd,
+            self.age,
+            self.tvl_trend,
+            self.reward_volatility,
+            self.peg,
+            self.impermanent_loss,
+            self.audit,
+            self.implementation,
+        ]
+    }
+}
+
+/// How the risk factors are judged from the farm and asset snapshots.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RiskParams {
+    /// Age in days from which a farm gets the full age score. Farms are as old as their
+    /// oldest snapshot, so this should stay within the snapshot retention.
+    #[serde(default = "default_mature_days")]
+    pub mature_days: u32,
+    /// Days over which the tvl trend is measured.
+    #[serde(default = "default_trend_days")]
+    pub trend_days: u32,
+    /// Days of daily reward token prices the volatility is measured over.
+    #[serde(default = "default_volatility_days")]
+    pub volatility_days: u32,
+    /// Standard deviation of daily log returns at which the volatility score reaches 0.
+    #[serde(default = "default_max_volatility")]
+    pub max_volatility: f64,
+    /// Deviation of a stable from its peg at which the peg score reaches 0.
+    #[serde(default = "default_max_peg_deviation")]
+    pub max_peg_deviation: f64,
+}
+
+impl Default for RiskParams {
+    fn default() -> Self {
+        RiskParams {
+            mature_days: default_mature_days(),
+            trend_days: default_trend_days(),
+            volatility_days: default_volatility_days(),
+            max_volatility: default_max_volatility(),
+            max_peg_deviation: default_max_peg_deviation(),
+        }
+    }
+}
+
+fn default_mature_days() -> u32 {
+    90
+}
+
+fn default_trend_days() -> u32 {
+    7
+}
+
+fn default_volatility_days() -> u32 {
+    30
+}
+
+fn default_max_volatility() -> f64 {
+    0.1
+}
+
+fn default_max_peg_deviation() -> f64 {
+    0.05
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditStatus {
+    /// Every contract farms go through has been audited.
+    Audited,
+    /// Some of them have.
+    Partial,
+    Unaudited,
+    #[default]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Audit {
+    pub protocol: String,
+    pub status: AuditStatus,
+}
+
+#[derive(Default, Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct AuditScores {
+    pub audited: f64,
+    pub partial: f64,
+    pub unaudited: f64,
+    pub unknown: f64,
+}
+
+impl AuditScores {
+    pub fn score(&self, status: AuditStatus) -> f64 {
+        match status {
+            AuditStatus::Audited => self.audited,
+            AuditStatus::Partial => self.partial,
+            AuditStatus::Unaudited => self.unaudited,
+            AuditStatus::Unknown => self.unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct TvlBucket {
+    /// Lowest tvl in usd the bucket's score applies from.
+    pub min: f64,
+    pub score: f64,
+}
+
+/// Reads the scoring methodology from `SCORING_CONFIG_PATH`, falling back to
+/// `DEFAULT_SCORING_CONFIG_PATH`.
+pub fn load_scoring() -> Result<Scoring, Box<dyn std::error::Error>> {
+    let path = dotenv::var("SCORING_CONFIG_PATH")
+        .unwrap_or_else(|_| DEFAULT_SCORING_CONFIG_PATH.to_string());
+    let contents =
+        fs::read_to_string(&path).map_err(|e| format!("couldn't read {}: {}", path, e))?;
+    parse_scoring(&contents)
+}
+
+fn parse_scoring(contents: &str) -> Result<Scoring, Box<dyn std::error::Error>> {
+    let mut scoring: Scoring = toml::from_str(contents)?;
+
+    if scoring.weights.all().iter().any(|w| *w < 0.0) {
+        return Err(format!("scoring {} has a negative weight", scoring.version).into());
+    }
+    let total: f64 = scoring.weights.all().iter().sum();
+    if (total - 1.0).abs() >= 1e-6 {
+        return Err(format!(
+            "scoring {} weights sum to {} instead of 1",
+            scoring.version, total
+        )
+        .into());
+    }
+    let risk = scoring.risk;
+    if risk.mature_days == 0
+        || risk.trend_days == 0
+        || risk.volatility_days == 0
+        || risk.max_volatility <= 0.0
+        || risk.max_peg_deviation <= 0.0
+    {
+        return Err(format!("scoring {} has a zero risk parameter", scoring.version).into());
+    }
+    if scoring.rules.iter().any(|r| r.reason.is_empty()) {
+        return Err(format!("scoring {} has a rule without a reason", scoring.version).into());
+    }
+    scoring.tvl_buckets.sort_by(|a, b| {
+        b.min
+            .partial_cmp(&a.min)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    Ok(scoring)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCORING: &str = r#"
+        version = "test"
+        tvl_buckets = []
+
+        [weights]
+        tvl = 0.25
+        base_apr = 0.25
+        reward_apr = 0.25
+        rewards_usd = 0.25
+
+        [[rules]]
+        action = "exclude"
+        reason = "excluded pools"
+        protocol = "stellaswap"
+        chef = "0xF3a5454496E26ac57da879bf3285Fa85DEBF0388"
+        ids = [31, 34]
+
+        [[rules]]
+        action = "exclude"
+        reason = "governance staking"
+        symbols = ["xStella"]
+
+        [[rules]]
+        action = "include"
+        reason = "sushi farms"
+        protocol = "sushiswap"
+
+        [[rules]]
+        action = "exclude"
+        reason = "inactive"
+        inactive = true
+    "#;
+
+    fn farm(protocol: &str, chef: &str, id: i32, symbol: &str, alloc_point: u32) -> models::Farm {
+        models::Farm {
+            id,
+            chef: chef.to_string(),
+            chain: "moonbeam".to_string(),
+            protocol: protocol.to_string(),
+            asset: models::FarmAsset {
+                symbol: symbol.to_string(),
+                ..Default::default()
+            },
+            alloc_point,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn shipped_scoring_is_valid() {
+        parse_scoring(include_str!("../config/scoring.toml")).unwrap();
+    }
+
+    #[test]
+    fn weights_must_sum_to_one() {
+        parse_scoring(SCORING).unwrap();
+        let skewed = SCORING.replace("tvl = 0.25", "tvl = 0.35");
+        assert_eq!(
+            parse_scoring(&skewed).unwrap_err().to_string(),
+            "scoring test weights sum to 1.1 instead of 1"
+        );
+    }
+
+    #[test]
+    fn rule_matches() {
+        let scoring = parse_scoring(SCORING).unwrap();
+        let pools = &scoring.rules[0];
+        let stella = "0xf3a5454496e26ac57da879bf3285fa85debf0388";
+        for (farm, matches) in [
+            (farm("stellaswap", stella, 31, "GLMR-USDC LP", 1), true),
+            (
+                farm("stellaswap", &stella.to_uppercase(), 34, "GLMR-USDC LP", 1),
+                true,
+            ),
+            (farm("stellaswap", stella, 32, "GLMR-USDC LP", 1), false),
+            (farm("stellaswap", "0xc", 31, "GLMR-USDC LP", 1), false),
+            (farm("solarflare", stella, 31, "GLMR-USDC LP", 1), false),
+        ] {
+            assert_eq!(pools.matches(&farm), matches, "{:?}", farm);
+        }
+
+        let inactive = &scoring.rules[3];
+        assert!(inactive.matches(&farm("beamswap", "0xc", 1, "GLMR-USDC LP", 0)));
+        assert!(!inactive.matches(&farm("beamswap", "0xc", 1, "GLMR-USDC LP", 1)));
+    }
+
+    #[test]
+    fn first_matching_rule_applies() {
+        let scoring = parse_scoring(SCORING).unwrap();
+        for (farm, reason) in [
+            (
+                farm("stellaswap", "0xc", 1, "xStella", 0),
+                Some("governance staking"),
+            ),
+            (
+                farm("sushiswap", "0xc", 1, "WETH-USDC LP", 0),
+                Some("sushi farms"),
+            ),
+            (
+                farm("beamswap", "0xc", 1, "GLMR-USDC LP", 0),
+                Some("inactive"),
+            ),
+            (farm("beamswap", "0xc", 1, "GLMR-USDC LP", 1), None),
+        ] {
+            assert_eq!(
+                scoring.rule(&farm).map(|r| r.reason.as_str()),
+                reason,
+                "{:?}",
+                farm
+            );
+        }
+        let sushi = farm("sushiswap", "0xc", 1, "WETH-USDC LP", 0);
+        assert_eq!(scoring.rule(&sushi).unwrap().action, RuleAction::Include);
+    }
+}
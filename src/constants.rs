@@ -148,13 +148,13 @@ pub mod chef {
         }
     "#;
 
-    pub const ONE_DAY_BLOCKS_QUERY: &str = r#"
-        query OneDayBlocks($start: Int!, $end: Int!) {
+    pub const BLOCKS_QUERY: &str = r#"
+        query Blocks($start: Int!, $end: Int!) {
             blocks(
                 first: 1
                 orderBy: timestamp
                 orderDirection: asc
-                where: { timestamp_gt: $start, timestamp_lt: $end }
+                where: { timestamp_gte: $start, timestamp_lt: $end }
             ) {
                 id
                 number
@@ -163,8 +163,8 @@ pub mod chef {
         }
     "#;
 
-    pub const ONE_DAY_POOLS_QUERY: &str = r#"
-        query OneDayPools($blocknum: Int!) {
+    pub const PAIR_VOLUMES_QUERY: &str = r#"
+        query PairVolumes($blocknum: Int!) {
             pairs(orderBy: reserveUSD, orderDirection: desc, first: 1000, block: { number: $blocknum }) {
                 id
                 reserveUSD
@@ -175,11 +175,11 @@ pub mod chef {
     "#;
 
     pub const PAIR_DAY_DATAS_QUERY: &str = r#"
-        query PairDayDatas($addr: String) {
+        query PairDayDatas($addr: String, $days: Int) {
             pairDayDatas(
                 orderDirection: desc
                 orderBy: date
-                first: 7
+                first: $days
                 where: {pairAddress: $addr}
             ) {
                 date
@@ -199,11 +199,11 @@ pub mod chef {
     "#;
 
     pub const SUSHI_PAIR_DAY_DATAS_QUERY: &str = r#"
-        query PairDayDatas($addr: String) {
+        query PairDayDatas($addr: String, $days: Int) {
             pairDayDatas(
                 orderDirection: desc
                 orderBy: date
-                first: 1
+                first: $days
                 skip: 1
                 where: {pair: $addr}
             ) {
@@ -224,14 +224,14 @@ pub mod chef {
     "#;
 
     pub const SOLARBEAM_STABLE_SWAPS_DAY_DATA_QUERY: &str = r#"
-        query Swap($addr: String) {
+        query Swap($addr: String, $days: Int) {
             swap(id: $addr) {
                 id
                 address
                 tokens {
                     symbol
                 }
-                dailyData(orderBy: timestamp, orderDirection: desc, first: 7) {
+                dailyData(orderBy: timestamp, orderDirection: desc, first: $days) {
                     id
                     timestamp
                     volume
@@ -241,14 +241,14 @@ pub mod chef {
     "#;
 
     pub const STELLASWAP_STABLE_SWAPS_DAY_DATA_QUERY: &str = r#"
-        query Swap($addr: String) {
+        query Swap($addr: String, $days: Int) {
             swap(id: $addr) {
                 id
                 address
                 tokens {
                     symbol
                 }
-                dailyVolumes(orderBy: timestamp, orderDirection: desc, first: 7) {
+                dailyVolumes(orderBy: timestamp, orderDirection: desc, first: $days) {
                     id
                     timestamp
                     volume
@@ -361,10 +361,10 @@ pub mod subsquid {
     "#;
 
     pub const PAIR_DAY_DATAS_QUERY: &str = r#"
-        query PairDayDatas($addr: String) {
+        query PairDayDatas($addr: String, $days: Int) {
             pairDayData(
                 orderBy: date_DESC
-                limit: 7
+                limit: $days
                 where: {pairAddress_eq: $addr}
             ) {
                 date
@@ -376,11 +376,11 @@ pub mod subsquid {
     "#;
 
     pub const STABLE_SWAPS_DAY_DATA_QUERY: &str = r#"
-        query {
+        query StableSwaps($days: Int) {
             stableSwaps {
                     id
                     lpToken
-                    stableSwapDayData(orderBy: date_DESC, limit: 7) {
+                    stableSwapDayData(orderBy: date_DESC, limit: $days) {
                         id
                         date
                         dailyVolumeUSD
@@ -391,59 +391,22 @@ pub mod subsquid {
 }
 
 pub mod subgraph_urls {
-    pub const SOLARBEAM_SUBGRAPH: &str =
-        "https://api.thegraph.com/subgraphs/name/solar-ape/solarbeam";
-    pub const SOLARFLARE_SUBGRAPH: &str =
-        "https://api.thegraph.com/subgraphs/name/solarbeamio/solarflare-subgraph";
-    pub const STELLASWAP_SUBGRAPH: &str =
-        "https://api.thegraph.com/subgraphs/name/stellaswap/stella-swap";
-    pub const BEAMSWAP_SUBGRAPH: &str =
-        "https://api.thegraph.com/subgraphs/name/beamswap/beamswap-dex";
-    pub const SUSHI_SUBGRAPH: &str =
-        "https://api.thegraph.com/subgraphs/name/sushiswap/exchange-moonriver";
-    pub const ZENLINK_ASTAR_SUBSQUID: &str =
-        "https://squid.subsquid.io/zenlink-astar-squid-yb/v/1/graphql";
-    pub const ZENLINK_MOONRIVER_SUBSQUID: &str =
-        "https://squid.subsquid.io/zenlink-moonriver-squid-yb/v/1/graphql";
-    pub const ZENLINK_MOONBEAM_SUBSQUID: &str =
-        "https://squid.subsquid.io/zenlink-moonbeam-squid-yb/v/1/graphql";
-
     pub const STELLASWAP_PULSAR_SUBGRAPH: &str =
         "https://api.thegraph.com/subgraphs/name/stellaswap/pulsar";
     pub const STELLASWAP_PULSAR_REWARDS_SUBGRAPH: &str =
         "https://api.thegraph.com/subgraphs/name/stellaswap/pulsar-farming";
-
-    pub const SOLARBEAM_STABLE_SUBGRAPH: &str =
-        "https://api.thegraph.com/subgraphs/name/capjacksparrow42/solarbeam-stable-amm";
-    pub const STELLASWAP_STABLE_SUBGRAPH: &str =
-        "https://api.thegraph.com/subgraphs/name/stellaswap/stable-amm";
-
-    pub const SOLARBEAM_BLOCKLYTICS_SUBGRAPH: &str =
-        "https://api.thegraph.com/subgraphs/name/solarbeamio/blocklytics";
-    pub const SOLARFLARE_BLOCKLYTICS_SUBGRAPH: &str =
-        "https://api.thegraph.com/subgraphs/name/solarbeamio/solarflare-blocklytics";
 }
 
 pub mod utils {
-    pub const MOONRIVER_BLOCK_TIME: f64 = 14.6;
-    pub const MOONBEAM_BLOCK_TIME: f64 = 12.4;
-    pub const ASTAR_BLOCK_TIME: f64 = 12.8;
-
     pub const TEN_F64: f64 = 10.0;
     pub const TEN_I128: i128 = 10;
 }
 
 pub mod addresses {
     pub mod arthswap_on_astar {
-        pub const ARTHSWAP_CHEF: &str = "0xc5b016c5597D298Fe9eD22922CE290A048aA5B75";
-        pub const ARTHSWAP_ROUTER: &str = "0xE915D2393a08a00c5A463053edD31bAe2199b9e7";
-
         pub const ARSW: &str = "0xDe2578Edec4669BA7F41c5d5D2386300bcEA4678";
     }
     pub mod solarbeam_on_moonriver {
-        pub const SOLARBEAM_CHEF: &str = "0x0329867a8c457e9F75e25b0685011291CD30904F";
-        pub const SOLARBEAM_ROUTER: &str = "0xAA30eF758139ae4a7f798112902Bf6d65612045f";
-
         pub const SOLAR: &str = "0x6bD193Ee6D2104F14F94E2cA6efefae561A4334B";
         pub const BUSD: &str = "0x5D9ab5522c64E1F6ef5e3627ECCc093f56167818";
         pub const USDC: &str = "0xE3F5a90F9cb311505cd691a46596599aA1A0AD7D";
@@ -467,29 +430,17 @@ pub mod addresses {
         pub const XCKSM_WSTKSM_LP: &str = "0x5568872bc43Bae3757F697c0e1b241b62Eddcc17";
     }
     pub mod solarflare_on_moonbeam {
-        pub const SOLARFLARE_CHEF: &str = "0x995da7dfB96B4dd1e2bd954bE384A1e66cBB4b8c";
-        pub const SOLARFLARE_ROUTER: &str = "0xd3B02Ff30c218c7f7756BA14bcA075Bf7C2C951e";
-
         pub const WGLMR: &str = "0xAcc15dC74880C9944775448304B263D191c6077F";
     }
     pub mod zenlink_on_moonriver {
-        pub const ZENLINK_CHEF: &str = "0xf4Ec122d32F2117674Ce127b72c40506c52A72F8";
-        pub const ZENLINK_ROUTER: &str = "0x1006Fff14E20fCc7D5975D4e81421bEcfb242Fa6";
-
         pub const USDC: &str = "0xE3F5a90F9cb311505cd691a46596599aA1A0AD7D";
         pub const XCAUSD: &str = "0xFfFffFFfa1B026a00FbAA67c86D5d1d5BF8D8228";
         pub const ZLK: &str = "0x0f47ba9d9Bde3442b42175e51d6A367928A1173B";
     }
     pub mod zenlink_on_moonbeam {
-        pub const ZENLINK_CHEF: &str = "0xD6708344553cd975189cf45AAe2AB3cd749661f4";
-        pub const ZENLINK_ROUTER: &str = "0x5C93cBF67C74daf14E36002D955eD5C7BD49887A";
-
         pub const ZLK: &str = "0x3Fd9b6C9A24E09F67b7b706d72864aEbb439100C";
     }
     pub mod zenlink_on_astar {
-        pub const ZENLINK_CHEF: &str = "0x460ee9DBc82B2Be84ADE50629dDB09f6A1746545";
-        pub const ZENLINK_ROUTER: &str = "0xf5016C2DF297457a1f9b036990cc704306264B40";
-
         pub const BAI: &str = "0x733ebcC6DF85f8266349DEFD0980f8Ced9B45f35";
         pub const BUSD: &str = "0x4Bf769b05E832FCdc9053fFFBC78Ca889aCb5E1E";
         pub const DAI: &str = "0x6De33698e9e9b787e09d3Bd7771ef63557E148bb";
@@ -497,10 +448,6 @@ pub mod addresses {
         pub const ZLK: &str = "0x998082C488e548820F970Df5173bD2061Ce90635";
     }
     pub mod stellaswap_on_moonbeam {
-        pub const STELLA_CHEF_V1: &str = "0xEDFB330F5FA216C9D2039B99C8cE9dA85Ea91c1E";
-        pub const STELLA_CHEF_V2: &str = "0xF3a5454496E26ac57da879bf3285Fa85DEBF0388";
-        pub const STELLA_ROUTER: &str = "0xd0A01ec574D1fC6652eDF79cb2F880fd47D34Ab1";
-
         pub const WGLMR: &str = "0xAcc15dC74880C9944775448304B263D191c6077F";
         pub const POOP: &str = "0xFFfffFFecB45aFD30a637967995394Cc88C0c194";
         pub const STELLA: &str = "0x0E358838ce72d5e61E0018a2ffaC4bEC5F4c88d2";
@@ -523,9 +470,6 @@ pub mod addresses {
         pub const WGLMR_POOP_LP: &str = "0x4EfB208eeEb5A8C85af70e8FBC43D6806b422bec";
     }
     pub mod beamswap_on_moonbeam {
-        pub const BEAM_CHEF: &str = "0xC6ca172FC8BDB803c5e12731109744fb0200587b";
-        pub const BEAM_ROUTER: &str = "0x96b244391D98B62D19aE89b1A4dCcf0fc56970C7";
-
         pub const WGLMR: &str = "0xAcc15dC74880C9944775448304B263D191c6077F";
         pub const POOP: &str = "0xFFfffFFecB45aFD30a637967995394Cc88C0c194";
         pub const BUSD: &str = "0xA649325Aa7C5093d12D6F98EB4378deAe68CE23F";
@@ -544,9 +488,6 @@ pub mod addresses {
         pub const XCDOT_WSTDOT_LP: &str = "0x79f05B32e29139C35Cd219aEDB5D99cedb1915aC";
     }
     pub mod sushi_on_moonriver {
-        pub const SUSHI_MINI_CHEF: &str = "0x3dB01570D97631f69bbb0ba39796865456Cf89A5";
-        pub const SUSHI_ROUTER: &str = "0x1b02dA8Cb0d097eB8D57A175b88c7D8b47997506";
-
         pub const SUSHI_COMPLEX_REWARDER: &str = "0x1334c8e873E1cae8467156e2A81d1C8b566B2da1";
 
         pub const SUSHI: &str = "0xf390830DF829cf22c53c8840554B98eafC5dCBc2";
@@ -131,3 +131,12 @@ abigen!(
         function totalSupply() external view returns (uint256)
     ]"#,
 );
+
+abigen!(
+    IMulticall3,
+    r#"[
+        struct Call3 { address target; bool allowFailure; bytes callData; }
+        struct Call3Result { bool success; bytes returnData; }
+        function aggregate3(Call3[] calls) external payable returns (Call3Result[])
+    ]"#,
+);
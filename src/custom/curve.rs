@@ -1,27 +1,14 @@
 use chrono::prelude::Utc;
-use mongodb::{
-    bson::{bson, doc},
-    options::{ClientOptions, FindOneAndUpdateOptions},
-    Client as MongoClient,
-};
 use std::collections::HashMap;
 
 use crate::apis;
 use crate::models;
 
 use crate::constants;
+use crate::store;
 // mod constants;
 
-pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Error>> {
-    let mut client_options = ClientOptions::parse(mongo_uri).await?;
-    client_options.app_name = Some("Bay Watcher".to_string());
-    let client = MongoClient::with_options(client_options)?;
-    let db_name = dotenv::var("DB_NAME").unwrap();
-    let db = client.database(&db_name);
-
-    let farms_collection = db.collection::<models::Farm>("farms");
-    let assets_collection = db.collection::<models::Asset>("assets");
-
+pub async fn curve_jobs(store: &dyn store::Store) -> Result<(), Box<dyn std::error::Error>> {
     let moonbeam_curve_st_dot = "0xc6e37086D09ec2048F151D11CdB9F9BbbdB7d685".to_string();
     let moonbeam_curve_d2o_xcusdt = "0xFF6DD348e6eecEa2d81D4194b60c5157CD9e64f4".to_string();
 
@@ -29,7 +16,7 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
         .await?
         .json::<apis::curve::GetFactoryAPYsRoot>()
         .await?;
-    println!(
+    log::debug!(
         "get_factory_apys_resp:\n{:#?}",
         get_factory_apys_resp.success
     );
@@ -39,14 +26,14 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
             if pd.pool_address == moonbeam_curve_st_dot.clone() {
                 // pd.apy
                 // pd.index
-                println!("pdddd index {} apy {}", pd.index, pd.apy);
+                log::debug!("pdddd index {} apy {}", pd.index, pd.apy);
 
                 let get_factory_v2_pools_resp =
                     reqwest::get("https://api.curve.fi/api/getFactoryV2Pools-moonbeam")
                         .await?
                         .json::<apis::curve::GetFactoryV2PoolsRoot>()
                         .await?;
-                println!(
+                log::debug!(
                     "get_factory_v2_pools_resp:\n{:#?}",
                     get_factory_v2_pools_resp.success
                 );
@@ -61,7 +48,7 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                     .await?
                                     .json::<apis::curve::GetFactoGaugesRoot>()
                                     .await?;
-                            println!(
+                            log::debug!(
                                 "get_facto_gauges_resp:\n{:#?}",
                                 get_facto_gauges_resp.success
                             );
@@ -92,19 +79,19 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                                     )
                                                         as f64;
                                                 let amount = rate * 60.0 * 60.0 * 24.0;
-                                                rewards.push(bson!({
-                                                    "amount": amount,
-                                                    "asset":  er.symbol,
-                                                    "valueUSD": amount * er.token_price,
-                                                    "freq": models::Freq::Daily.to_string(),
-                                                }));
+                                                rewards.push(models::Reward {
+                                                    amount,
+                                                    asset: er.symbol,
+                                                    value_usd: amount * er.token_price,
+                                                    freq: models::Freq::Daily.to_string(),
+                                                });
                                                 total_apy += er.apy;
                                             }
                                         }
 
                                         let timestamp = Utc::now().to_string();
 
-                                        println!(
+                                        log::debug!(
                                             "curve v2 farm lastUpdatedAtUTC {}",
                                             timestamp.clone()
                                         );
@@ -114,16 +101,20 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                         let mut logo1 = "stDOT";
                                         let mut chef = "0xC106C836771B0B4f4a0612Bd68163Ca93be1D340";
                                         let mut underlyingAssets = vec![
-                                            bson!({
-                                                "symbol": "xcDOT",
-                                                "address":  "0xFfFFfFff1FcaCBd218EDc0EbA20Fc2308C778080",
-                                                "decimals": 10,
-                                            }),
-                                            bson!({
-                                                "symbol": "stDOT",
-                                                "address":  "0xFA36Fe1dA08C89eC72Ea1F0143a35bFd5DAea108",
-                                                "decimals": 10,
-                                            }),
+                                            models::UnderlyingAsset {
+                                                symbol: "xcDOT".to_string(),
+                                                address:
+                                                    "0xFfFFfFff1FcaCBd218EDc0EbA20Fc2308C778080"
+                                                        .to_string(),
+                                                decimals: 10,
+                                            },
+                                            models::UnderlyingAsset {
+                                                symbol: "stDOT".to_string(),
+                                                address:
+                                                    "0xFA36Fe1dA08C89eC72Ea1F0143a35bFd5DAea108"
+                                                        .to_string(),
+                                                decimals: 10,
+                                            },
                                         ];
                                         if pd.pool_address.clone()
                                             == moonbeam_curve_d2o_xcusdt.clone()
@@ -133,18 +124,22 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                             logo1 = "xcUSDT";
                                             chef = "0x4efb9942e50aB8bBA4953F71d8Bebd7B2dcdE657";
                                             underlyingAssets = vec![
-                                                bson!({
-                                                    "symbol": "d2O",
-                                                    "address":  "0xc806B0600cbAfA0B197562a9F7e3B9856866E9bF",
-                                                    "decimals": 18,
-                                                }),
-                                                bson!({
-                                                    "symbol": "xcUSDT",
-                                                    "address":  "0xFFFFFFfFea09FB06d082fd1275CD48b191cbCD1d",
-                                                    "decimals": 6,
-                                                }),
+                                                models::UnderlyingAsset {
+                                                    symbol: "d2O".to_string(),
+                                                    address:
+                                                        "0xc806B0600cbAfA0B197562a9F7e3B9856866E9bF"
+                                                            .to_string(),
+                                                    decimals: 18,
+                                                },
+                                                models::UnderlyingAsset {
+                                                    symbol: "xcUSDT".to_string(),
+                                                    address:
+                                                        "0xFFFFFFfFea09FB06d082fd1275CD48b191cbCD1d"
+                                                            .to_string(),
+                                                    decimals: 6,
+                                                },
                                             ];
-                                            println!("case2 total_apy {}", total_apy);
+                                            log::debug!("case2 total_apy {}", total_apy);
                                         }
 
                                         let virtual_price: f64 = pda.virtual_price
@@ -153,82 +148,55 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                             pda.total_supply.parse::<f64>().unwrap_or_default()
                                                 / constants::utils::TEN_F64.powf(18.0);
 
-                                        let ff = doc! {
-                                            "id": pd.index as i32,
-                                            "chef": chef,
-                                            "chain": "moonbeam",
-                                            "protocol": "curve",
-                                        };
-
-                                        let fu = doc! {
-                                            "$set" : {
-                                                "id": pd.index,
-                                                "chef": chef,
-                                                "chain": "moonbeam",
-                                                "protocol": "curve",
-                                                "farmType": models::FarmType::StableAmm.to_string(),
-                                                "farmImpl": models::FarmImplementation::Vyper.to_string(),
-                                                "router": pd.pool_address.clone(),
-                                                "asset": {
-                                                    "symbol": symbol,
-                                                    "address": pd.pool_address.clone(),
-                                                    "price": 0,
-                                                    "logos": [
-                                                        format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo0),
-                                                        format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo1),
-                                                    ],
-                                                    "underlyingAssets": underlyingAssets.clone(),
-                                                },
-                                                "tvl": pda.usd_total as f64,
-                                                "apr.reward": total_apy,
-                                                "apr.base": pd.apy,
-                                                "rewards": rewards,
-                                                "allocPoint": 1,
-                                                "lastUpdatedAtUTC": timestamp.clone(),
-                                            }
+                                        let farm = models::Farm {
+                                            id: pd.index as i32,
+                                            chef: chef.to_string(),
+                                            router: pd.pool_address.clone(),
+                                            chain: "moonbeam".to_string(),
+                                            protocol: "curve".to_string(),
+                                            farm_type: models::FarmType::StableAmm.to_string(),
+                                            farm_impl: models::FarmImplementation::Vyper.to_string(),
+                                            asset: models::FarmAsset {
+                                                symbol: symbol.to_string(),
+                                                address: pd.pool_address.clone(),
+                                                price: 0.0,
+                                                logos: vec![format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo0), format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo1)],
+                                                underlying_assets: underlyingAssets.clone(),
+                                            },
+                                            tvl: pda.usd_total as f64,
+                                            apr: models::APR {
+                                                reward: total_apy,
+                                                base: pd.apy,
+                                            },
+                                            rewards,
+                                            alloc_point: 1,
+                                            last_updated_at_utc: timestamp.clone(),
+                                            ..Default::default()
                                         };
-                                        let options = FindOneAndUpdateOptions::builder()
-                                            .upsert(Some(true))
-                                            .build();
-                                        farms_collection
-                                            .find_one_and_update(ff, fu, Some(options))
-                                            .await?;
-
-                                        let f = doc! {
-                                            "address": pd.pool_address.clone(),
-                                            "chain": "moonbeam",
-                                            "protocol": "curve",
-                                        };
-
-                                        let u = doc! {
-                                            "$set" : {
-                                                "address": pd.pool_address.clone(),
-                                                "chain": "moonbeam",
-                                                "protocol": "curve",
-                                                "name": symbol,
-                                                "symbol": symbol,
-                                                "decimals": 18,
-                                                "logos": [
-                                                    format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo0),
-                                                    format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo1),
-                                                ],
-                                                "price": g.lp_token_price,
-                                                "liquidity": total_supply * g.lp_token_price,
-                                                "totalSupply": total_supply,
-                                                "isLP": true,
-                                                "feesAPR": pd.apy,
-                                                "underlyingAssets": underlyingAssets,
-                                                "underlyingAssetsAlloc": [],
-                                                "lastUpdatedAtUTC": timestamp.clone(),
-                                            }
+                                        store.upsert_farm(&farm).await?;
+
+                                        let asset = models::Asset {
+                                            address: pd.pool_address.clone(),
+                                            chain: "moonbeam".to_string(),
+                                            protocol: "curve".to_string(),
+                                            name: symbol.to_string(),
+                                            symbol: symbol.to_string(),
+                                            decimals: 18,
+                                            logos: vec![format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo0), format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo1)],
+                                            price: g.lp_token_price,
+                                            price_source: models::PriceSource::Protocol,
+                                            price_confidence: models::PriceConfidence::High,
+                                            peg: None,
+                                            liquidity: total_supply * g.lp_token_price,
+                                            total_supply,
+                                            is_lp: true,
+                                            fees_apr: pd.apy,
+                                            underlying_assets: underlyingAssets,
+                                            underlying_assets_alloc: vec![],
+                                            last_updated_at_utc: timestamp.clone(),
                                         };
 
-                                        let options = FindOneAndUpdateOptions::builder()
-                                            .upsert(Some(true))
-                                            .build();
-                                        assets_collection
-                                            .find_one_and_update(f, u, Some(options))
-                                            .await?;
+                                        store.upsert_asset(&asset).await?;
                                     }
                                 }
                             }
@@ -238,14 +206,14 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
             } else if pd.pool_address == moonbeam_curve_d2o_xcusdt.clone() {
                 // pd.apy
                 // pd.index
-                println!("pdddd index {} apy {}", pd.index, pd.apy);
+                log::debug!("pdddd index {} apy {}", pd.index, pd.apy);
 
                 let get_factory_v2_pools_resp =
                     reqwest::get("https://api.curve.fi/api/getFactoryV2Pools-moonbeam")
                         .await?
                         .json::<apis::curve::GetFactoryV2PoolsRoot>()
                         .await?;
-                println!(
+                log::debug!(
                     "get_factory_v2_pools_resp:\n{:#?}",
                     get_factory_v2_pools_resp.success
                 );
@@ -260,7 +228,7 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                     .await?
                                     .json::<apis::curve::GetFactoGaugesRoot>()
                                     .await?;
-                            println!(
+                            log::debug!(
                                 "get_facto_gauges_resp:\n{:#?}",
                                 get_facto_gauges_resp.success
                             );
@@ -291,19 +259,19 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                                     )
                                                         as f64;
                                                 let amount = rate * 60.0 * 60.0 * 24.0;
-                                                rewards.push(bson!({
-                                                    "amount": amount,
-                                                    "asset":  er.symbol,
-                                                    "valueUSD": amount * er.token_price,
-                                                    "freq": models::Freq::Daily.to_string(),
-                                                }));
+                                                rewards.push(models::Reward {
+                                                    amount,
+                                                    asset: er.symbol,
+                                                    value_usd: amount * er.token_price,
+                                                    freq: models::Freq::Daily.to_string(),
+                                                });
                                                 total_apy += er.apy;
                                             }
                                         }
 
                                         let timestamp = Utc::now().to_string();
 
-                                        println!(
+                                        log::debug!(
                                             "curve v2 farm lastUpdatedAtUTC {}",
                                             timestamp.clone()
                                         );
@@ -313,16 +281,20 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                         let mut logo1 = "stDOT";
                                         let mut chef = "0xC106C836771B0B4f4a0612Bd68163Ca93be1D340";
                                         let mut underlyingAssets = vec![
-                                            bson!({
-                                                "symbol": "xcDOT",
-                                                "address":  "0xFfFFfFff1FcaCBd218EDc0EbA20Fc2308C778080",
-                                                "decimals": 10,
-                                            }),
-                                            bson!({
-                                                "symbol": "stDOT",
-                                                "address":  "0xFA36Fe1dA08C89eC72Ea1F0143a35bFd5DAea108",
-                                                "decimals": 10,
-                                            }),
+                                            models::UnderlyingAsset {
+                                                symbol: "xcDOT".to_string(),
+                                                address:
+                                                    "0xFfFFfFff1FcaCBd218EDc0EbA20Fc2308C778080"
+                                                        .to_string(),
+                                                decimals: 10,
+                                            },
+                                            models::UnderlyingAsset {
+                                                symbol: "stDOT".to_string(),
+                                                address:
+                                                    "0xFA36Fe1dA08C89eC72Ea1F0143a35bFd5DAea108"
+                                                        .to_string(),
+                                                decimals: 10,
+                                            },
                                         ];
                                         if pd.pool_address.clone()
                                             == moonbeam_curve_d2o_xcusdt.clone()
@@ -332,18 +304,22 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                             logo1 = "xcUSDT";
                                             chef = "0x4efb9942e50aB8bBA4953F71d8Bebd7B2dcdE657";
                                             underlyingAssets = vec![
-                                                bson!({
-                                                    "symbol": "d2O",
-                                                    "address":  "0xc806B0600cbAfA0B197562a9F7e3B9856866E9bF",
-                                                    "decimals": 18,
-                                                }),
-                                                bson!({
-                                                    "symbol": "xcUSDT",
-                                                    "address":  "0xFFFFFFfFea09FB06d082fd1275CD48b191cbCD1d",
-                                                    "decimals": 6,
-                                                }),
+                                                models::UnderlyingAsset {
+                                                    symbol: "d2O".to_string(),
+                                                    address:
+                                                        "0xc806B0600cbAfA0B197562a9F7e3B9856866E9bF"
+                                                            .to_string(),
+                                                    decimals: 18,
+                                                },
+                                                models::UnderlyingAsset {
+                                                    symbol: "xcUSDT".to_string(),
+                                                    address:
+                                                        "0xFFFFFFfFea09FB06d082fd1275CD48b191cbCD1d"
+                                                            .to_string(),
+                                                    decimals: 6,
+                                                },
                                             ];
-                                            println!("case2 total_apy {}", total_apy);
+                                            log::debug!("case2 total_apy {}", total_apy);
                                         }
 
                                         let virtual_price: f64 = pda.virtual_price
@@ -352,82 +328,55 @@ pub async fn curve_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Err
                                             pda.total_supply.parse::<f64>().unwrap_or_default()
                                                 / constants::utils::TEN_F64.powf(18.0);
 
-                                        let ff = doc! {
-                                            "id": pd.index as i32,
-                                            "chef": chef,
-                                            "chain": "moonbeam",
-                                            "protocol": "curve",
-                                        };
-
-                                        let fu = doc! {
-                                            "$set" : {
-                                                "id": pd.index,
-                                                "chef": chef,
-                                                "chain": "moonbeam",
-                                                "protocol": "curve",
-                                                "farmType": models::FarmType::StableAmm.to_string(),
-                                                "farmImpl": models::FarmImplementation::Vyper.to_string(),
-                                                "router": pd.pool_address.clone(),
-                                                "asset": {
-                                                    "symbol": symbol,
-                                                    "address": pd.pool_address.clone(),
-                                                    "price": 0,
-                                                    "logos": [
-                                                        format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo0),
-                                                        format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo1),
-                                                    ],
-                                                    "underlyingAssets": underlyingAssets.clone(),
-                                                },
-                                                "tvl": pda.usd_total as f64,
-                                                "apr.reward": total_apy,
-                                                "apr.base": pd.apy,
-                                                "rewards": rewards,
-                                                "allocPoint": 1,
-                                                "lastUpdatedAtUTC": timestamp.clone(),
-                                            }
-                                        };
-                                        let options = FindOneAndUpdateOptions::builder()
-                                            .upsert(Some(true))
-                                            .build();
-                                        farms_collection
-                                            .find_one_and_update(ff, fu, Some(options))
-                                            .await?;
-
-                                        let f = doc! {
-                                            "address": pd.pool_address.clone(),
-                                            "chain": "moonbeam",
-                                            "protocol": "curve",
+                                        let farm = models::Farm {
+                                            id: pd.index as i32,
+                                            chef: chef.to_string(),
+                                            router: pd.pool_address.clone(),
+                                            chain: "moonbeam".to_string(),
+                                            protocol: "curve".to_string(),
+                                            farm_type: models::FarmType::StableAmm.to_string(),
+                                            farm_impl: models::FarmImplementation::Vyper.to_string(),
+                                            asset: models::FarmAsset {
+                                                symbol: symbol.to_string(),
+                                                address: pd.pool_address.clone(),
+                                                price: 0.0,
+                                                logos: vec![format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo0), format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo1)],
+                                                underlying_assets: underlyingAssets.clone(),
+                                            },
+                                            tvl: pda.usd_total as f64,
+                                            apr: models::APR {
+                                                reward: total_apy,
+                                                base: pd.apy,
+                                            },
+                                            rewards,
+                                            alloc_point: 1,
+                                            last_updated_at_utc: timestamp.clone(),
+                                            ..Default::default()
                                         };
-
-                                        let u = doc! {
-                                            "$set" : {
-                                                "address": pd.pool_address.clone(),
-                                                "chain": "moonbeam",
-                                                "protocol": "curve",
-                                                "name": symbol,
-                                                "symbol": symbol,
-                                                "decimals": 18,
-                                                "logos": [
-                                                    format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo0),
-                                                    format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo1),
-                                                ],
-                                                "price": g.lp_token_price,
-                                                "liquidity": total_supply * g.lp_token_price,
-                                                "totalSupply": total_supply,
-                                                "isLP": true,
-                                                "feesAPR": pd.apy,
-                                                "underlyingAssets": underlyingAssets,
-                                                "underlyingAssetsAlloc": [],
-                                                "lastUpdatedAtUTC": timestamp.clone(),
-                                            }
+                                        store.upsert_farm(&farm).await?;
+
+                                        let asset = models::Asset {
+                                            address: pd.pool_address.clone(),
+                                            chain: "moonbeam".to_string(),
+                                            protocol: "curve".to_string(),
+                                            name: symbol.to_string(),
+                                            symbol: symbol.to_string(),
+                                            decimals: 18,
+                                            logos: vec![format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo0), format!("https://raw.githubusercontent.com/yield-bay/assets/main/list/{}.png", logo1)],
+                                            price: g.lp_token_price,
+                                            price_source: models::PriceSource::Protocol,
+                                            price_confidence: models::PriceConfidence::High,
+                                            peg: None,
+                                            liquidity: total_supply * g.lp_token_price,
+                                            total_supply,
+                                            is_lp: true,
+                                            fees_apr: pd.apy,
+                                            underlying_assets: underlyingAssets,
+                                            underlying_assets_alloc: vec![],
+                                            last_updated_at_utc: timestamp.clone(),
                                         };
 
-                                        let options = FindOneAndUpdateOptions::builder()
-                                            .upsert(Some(true))
-                                            .build();
-                                        assets_collection
-                                            .find_one_and_update(f, u, Some(options))
-                                            .await?;
+                                        store.upsert_asset(&asset).await?;
                                     }
                                 }
                             }
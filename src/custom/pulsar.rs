@@ -2,36 +2,25 @@ use std::collections::HashMap;
 
 use chrono::prelude::Utc;
 use ethers::{
-    middleware::SignerMiddleware,
     prelude::{Address, U256},
     providers::{Http, Provider},
-    signers::LocalWallet,
     utils::to_checksum,
 };
 use gql_client::Client;
-use mongodb::{
-    bson::{bson, doc, Bson},
-    options::{ClientOptions, FindOneAndUpdateOptions},
-    Client as MongoClient,
-};
 use serde::Serialize;
 use struct_iterable::Iterable;
 
 use crate::apis;
 use crate::constants;
 use crate::models;
+use crate::store;
 use crate::subgraph;
 
-pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Error>> {
-    println!("starting pulsar");
-    let mut client_options = ClientOptions::parse(mongo_uri).await?;
-    client_options.app_name = Some("Bay Watcher".to_string());
-    let client = MongoClient::with_options(client_options)?;
-    let db_name = dotenv::var("DB_NAME").unwrap();
-    let db = client.database(&db_name);
+/// Pulsar farms have no chef contract or pid, they're all written with id 0 under this chef.
+pub const CHEF: &str = "pulsar";
 
-    let farms_collection = db.collection::<models::Farm>("farms");
-    let assets_collection = db.collection::<models::Asset>("assets");
+pub async fn pulsar_jobs(store: &dyn store::Store) -> Result<(), Box<dyn std::error::Error>> {
+    log::debug!("starting pulsar");
 
     let subgraph_client = Client::new(
         constants::subgraph_urls::STELLASWAP_PULSAR_SUBGRAPH.to_string(),
@@ -42,7 +31,7 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
         .query_unwrap::<subgraph::PulsarData>(constants::chef::LISTED_POOLS_QUERY.clone())
         .await;
 
-    // println!("pool_data {:?}", pool_data);
+    // log::debug!("pool_data {:?}", pool_data);
 
     let rewards_subgraph_client = Client::new(
         constants::subgraph_urls::STELLASWAP_PULSAR_REWARDS_SUBGRAPH.to_string(),
@@ -55,14 +44,14 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
         )
         .await;
 
-    // println!("pool_rewards_data {:?}", pool_rewards_data);
+    // log::debug!("pool_rewards_data {:?}", pool_rewards_data);
 
     let reward_apr_resp = reqwest::get("https://apr-api.stellaswap.com/api/v1/eternalAPR")
         .await?
         .json::<apis::pulsar::Root>()
         .await?;
 
-    // println!("reward_apr_resp {:?}", reward_apr_resp);
+    // log::debug!("reward_apr_resp {:?}", reward_apr_resp);
 
     let base_apr_resp = reqwest::get("https://apr-api.stellaswap.com/api/v1/poolsAPR")
         .await?
@@ -83,9 +72,9 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
         "0xdd228d01e11041050be93fcfaf3005930782810e".to_string(),
         "0xda6381c6c5a20bc03d0dac2bf9d165d5775f41f7".to_string(),
     ];
-    // println!("base_apr_resp {:?}", base_apr_resp);
+    // log::debug!("base_apr_resp {:?}", base_apr_resp);
     // base_apr_resp.result
-    println!("fetched all data");
+    log::debug!("fetched all data");
     if pool_data.is_ok() {
         if pool_rewards_data.is_ok() {
             let mut h: HashMap<String, subgraph::EternalFarming> = HashMap::new();
@@ -101,7 +90,7 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
                 let mut base_apr_map: HashMap<String, f64> = HashMap::new();
                 if base_apr_resp.is_success {
                     for (x, v) in base_apr_resp.result.iter() {
-                        // println!(
+                        // log::debug!(
                         //     "xxx {:?} {:?}",
                         //     &x[1..].to_string(),
                         //     v.downcast_ref::<f64>().unwrap()
@@ -112,12 +101,12 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
                     for pool in pool_data.clone().unwrap().pools {
                         let timestamp = Utc::now().to_string();
 
-                        println!("pulsar farm lastUpdatedAtUTC {}", timestamp.clone());
+                        log::debug!("pulsar farm lastUpdatedAtUTC {}", timestamp.clone());
 
                         let tvl: f64 = pool.total_value_locked_usd.parse().unwrap_or_default();
 
                         if listed_pools.contains(&pool.id.clone()) {
-                            println!("contains");
+                            log::debug!("contains");
                             let mut base_apr = 0.0;
                             if base_apr_map.get(&pool.id).is_some() {
                                 base_apr = *base_apr_map.get(&pool.id).unwrap();
@@ -127,7 +116,7 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
                                 reward_apr = *reward_apr_map.get(&pool.id).unwrap();
                             }
 
-                            // println!(
+                            // log::debug!(
                             //     "{:?}: {:?}-{:?} LP, tvl: {:?}, base apr: {:?}, reward apr: {:?}, rewards: {:#?}",
                             //     pool.id,
                             //     pool.token0.symbol,
@@ -138,7 +127,7 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
                             //     h.get(&pool.id)
                             // );
 
-                            let mut rewards: Vec<Bson> = vec![];
+                            let mut rewards: Vec<models::Reward> = vec![];
 
                             if h.get(&pool.id).is_some() {
                                 let mut rr = 0.0;
@@ -150,7 +139,7 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
                                     brr =
                                         h.get(&pool.id).unwrap().bonus_reward_rate.parse().unwrap();
                                 }
-                                println!(
+                                log::debug!(
                                     "pid: {:?}: rr {:?}, brr {:?}",
                                     pool.id.clone(),
                                     rr * 86400.0,
@@ -167,48 +156,47 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
                                     &rt.as_str().parse::<Address>()?,
                                     None,
                                 );
-                                // println!("rt_asset_addr {:?}", asset_addr.clone());
-                                let rt_asset_filter = doc! { "address": rt_asset_addr.clone(), "protocol": "stellaswap", "chain": "moonbeam" };
-                                let rt_asset =
-                                    assets_collection.find_one(rt_asset_filter, None).await?;
-                                println!("rt_asset {:?}", rt_asset);
+                                // log::debug!("rt_asset_addr {:?}", asset_addr.clone());
+                                let rt_asset = store
+                                    .find_asset(&rt_asset_addr, "moonbeam", "stellaswap")
+                                    .await?;
+                                log::debug!("rt_asset {:?}", rt_asset);
                                 let brt = &h.get(&pool.id).unwrap().bonus_reward_token;
 
                                 let brt_asset_addr = ethers::utils::to_checksum(
                                     &brt.as_str().parse::<Address>()?,
                                     None,
                                 );
-                                // println!("brt_asset_addr {:?}", asset_addr.clone());
-                                let brt_asset_filter = doc! { "address": brt_asset_addr.clone(), "protocol": "stellaswap", "chain": "moonbeam" };
-                                let brt_asset =
-                                    assets_collection.find_one(brt_asset_filter, None).await?;
-                                println!("brt_asset {:?}", brt_asset);
-                                println!(
-                                    "rrrr {:?}, {:?}, {:?}, {:?}",
-                                    rr,
-                                    constants::utils::TEN_F64
-                                        .powf(rt_asset.clone().unwrap().decimals as f64),
-                                    rr / constants::utils::TEN_F64
-                                        .powf(rt_asset.clone().unwrap().decimals as f64),
-                                    rr / constants::utils::TEN_F64
-                                        .powf(rt_asset.clone().unwrap().decimals as f64)
-                                        * rt_asset.clone().unwrap().price,
-                                );
-                                if rt_asset.is_some() {
-                                    rewards.push(bson!({
-                                        "amount": rr * 86400.0 / constants::utils::TEN_F64.powf(rt_asset.clone().unwrap().decimals as f64) as f64,
-                                        "asset":  rt_asset.clone().unwrap().symbol,
-                                        "valueUSD": (rr * 86400.0 / constants::utils::TEN_F64.powf(rt_asset.clone().unwrap().decimals as f64) as f64) * rt_asset.clone().unwrap().price,
-                                        "freq": models::Freq::Daily.to_string(),
-                                    }))
-                                }
-                                if brt_asset.is_some() {
-                                    rewards.push(bson!({
-                                        "amount": brr * 86400.0 / constants::utils::TEN_F64.powf(brt_asset.clone().unwrap().decimals as f64) as f64,
-                                        "asset":  brt_asset.clone().unwrap().symbol,
-                                        "valueUSD": (brr * 86400.0 / constants::utils::TEN_F64.powf(brt_asset.clone().unwrap().decimals as f64) as f64) * brt_asset.clone().unwrap().price,
-                                        "freq": models::Freq::Daily.to_string(),
-                                    }))
+                                // log::debug!("brt_asset_addr {:?}", asset_addr.clone());
+                                let brt_asset = store
+                                    .find_asset(&brt_asset_addr, "moonbeam", "stellaswap")
+                                    .await?;
+                                log::debug!("brt_asset {:?}", brt_asset);
+                                // a reward token that isn't an asset yet leaves the pool
+                                // without that reward rather than failing it
+                                for (rate, addr, asset) in [
+                                    (rr, &rt_asset_addr, rt_asset),
+                                    (brr, &brt_asset_addr, brt_asset),
+                                ] {
+                                    match asset {
+                                        Some(asset) => {
+                                            let amount = rate * 86400.0
+                                                / constants::utils::TEN_F64
+                                                    .powf(asset.decimals as f64);
+                                            rewards.push(models::Reward {
+                                                amount,
+                                                asset: asset.symbol,
+                                                value_usd: amount * asset.price,
+                                                freq: models::Freq::Daily.to_string(),
+                                            })
+                                        }
+                                        None if rate != 0.0 => log::warn!(
+                                            "missing pulsar reward asset {} of pool {}",
+                                            addr,
+                                            pool.id
+                                        ),
+                                        None => {}
+                                    }
                                 }
                             }
 
@@ -221,43 +209,38 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
                                 pool.token1.symbol
                             );
 
-                            let ff = doc! {
-                                "id": 0,
-                                "chef": "pulsar".to_string(),
-                                "chain": "moonbeam".to_string(),
-                                "protocol": "Stellaswap Pulsar".to_string(),
-                                "asset.address": pool.id.clone(),
+                            let farm = models::Farm {
+                                id: 0,
+                                chef: CHEF.to_string(),
+                                router: "".to_string(),
+                                chain: "moonbeam".to_string(),
+                                protocol: "Stellaswap Pulsar".to_string(),
+                                farm_type: models::FarmType::ConcentratedLiquidity.to_string(),
+                                farm_impl: models::FarmImplementation::Solidity.to_string(),
+                                asset: models::FarmAsset {
+                                    symbol: format!(
+                                        "{}-{} LP",
+                                        pool.token0.symbol, pool.token1.symbol
+                                    ),
+                                    address: pool.id.clone(),
+                                    price: 0 as f64,
+                                    logos: vec![token0logo, token1logo],
+                                    underlying_assets: vec![
+                                        underlying_asset(&pool.token0)?,
+                                        underlying_asset(&pool.token1)?,
+                                    ],
+                                },
+                                tvl,
+                                apr: models::APR {
+                                    reward: reward_apr,
+                                    base: base_apr,
+                                },
+                                rewards,
+                                alloc_point: 1,
+                                last_updated_at_utc: timestamp.clone(),
+                                ..Default::default()
                             };
-                            let fu = doc! {
-                                "$set" : {
-                                    "id": 0,
-                                    "chef": "pulsar".to_string(),
-                                    "chain": "moonbeam".to_string(),
-                                    "protocol": "Stellaswap Pulsar".to_string(),
-                                    "farmType": models::FarmType::ConcentratedLiquidity.to_string(),
-                                    "farmImpl": models::FarmImplementation::Solidity.to_string(),
-                                    "router": "".to_string(),
-                                    "asset": {
-                                        "symbol": format!("{}-{} LP", pool.token0.symbol, pool.token1.symbol),
-                                        "address": pool.id.clone(),
-                                        "price": 0 as f64,
-                                        "logos": [token0logo, token1logo],
-                                        "underlyingAssets": [],
-                                    },
-                                    "tvl": tvl,
-                                    "apr.reward": reward_apr,
-                                    "apr.base": base_apr,
-                                    "rewards": rewards,
-                                    "allocPoint": 1,
-                                    "lastUpdatedAtUTC": timestamp.clone(),
-                                }
-                            };
-                            let options = FindOneAndUpdateOptions::builder()
-                                .upsert(Some(true))
-                                .build();
-                            farms_collection
-                                .find_one_and_update(ff, fu, Some(options))
-                                .await?;
+                            store.upsert_farm(&farm).await?;
                         }
                     }
                 }
@@ -265,6 +248,17 @@ pub async fn pulsar_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Er
         }
     }
 
-    println!("finished pulsar");
+    log::debug!("finished pulsar");
     Ok(())
 }
+
+/// A pool token as a farm's underlying asset, with the checksummed address assets are stored under.
+fn underlying_asset(
+    token: &subgraph::PulsarToken,
+) -> Result<models::UnderlyingAsset, Box<dyn std::error::Error>> {
+    Ok(models::UnderlyingAsset {
+        symbol: token.symbol.clone(),
+        address: to_checksum(&token.id.parse::<Address>()?, None),
+        decimals: token.decimals.parse()?,
+    })
+}
@@ -1,39 +1,27 @@
 use chrono::prelude::Utc;
 use gql_client::Client;
-use mongodb::{
-    bson::{bson, doc, Bson},
-    options::{ClientOptions, FindOneAndUpdateOptions},
-    Client as MongoClient,
-};
 use serde::Serialize;
 
 use crate::apis;
 use crate::constants;
 use crate::models;
+use crate::store;
 use crate::subgraph;
 
-pub async fn tapio_taiga_jobs(mongo_uri: String) -> Result<(), Box<dyn std::error::Error>> {
-    let mut client_options = ClientOptions::parse(mongo_uri).await?;
-    client_options.app_name = Some("Bay Watcher".to_string());
-    let client = MongoClient::with_options(client_options)?;
-    let db_name = dotenv::var("DB_NAME").unwrap();
-    let db = client.database(&db_name);
-
-    let farms_collection = db.collection::<models::Farm>("farms");
-
+pub async fn tapio_taiga_jobs(store: &dyn store::Store) -> Result<(), Box<dyn std::error::Error>> {
     let tapio_rewards_resp = reqwest::get("https://api.taigaprotocol.io/rewards?network=acala")
         .await?
         .json::<apis::tapio::Root>()
         .await?;
 
-    println!("tapio_rewards_resp {:?}", tapio_rewards_resp);
+    log::debug!("tapio_rewards_resp {:?}", tapio_rewards_resp);
 
     let taiga_rewards_resp = reqwest::get("https://api.taigaprotocol.io/rewards")
         .await?
         .json::<apis::taiga::Root>()
         .await?;
 
-    println!("taiga_rewards_resp {:?}", taiga_rewards_resp);
+    log::debug!("taiga_rewards_resp {:?}", taiga_rewards_resp);
 
     if taiga_rewards_resp.clone().taiksm.is_some() {
         let tai_ksm_base_apr = taiga_rewards_resp.clone().taiksm.unwrap().taiksm_fee.apr * 100.0;
@@ -52,56 +40,49 @@ pub async fn tapio_taiga_jobs(mongo_uri: String) -> Result<(), Box<dyn std::erro
         if _tai_ksm.0 != 0.0 {
             let mut tai_ksm_rewards = vec![];
             for r in _tai_ksm.1.clone() {
-                tai_ksm_rewards.push(bson!({
-                    "amount": r.0 as f64,
-                    "asset":  r.1.clone(),
-                    "valueUSD": r.2 as f64,
-                    "freq": r.3.clone(),
-                }));
+                tai_ksm_rewards.push(models::Reward {
+                    amount: r.0 as f64,
+                    asset: r.1.clone(),
+                    value_usd: r.2 as f64,
+                    freq: r.3.clone(),
+                });
             }
 
             let timestamp = Utc::now().to_string();
 
-            println!("taiKSM farm lastUpdatedAtUTC {}", timestamp.clone());
-
-            let tai_ksm_ff = doc! {
-                "id": 0,
-                "chef": "taiKSM".to_string(),
-                "chain": "karura".to_string(),
-                "protocol": "taiga".to_string(),
-            };
-            let tai_ksm_fu = doc! {
-                "$set" : {
-                    "id": 0,
-                    "chef": "taiKSM".to_string(),
-                    "chain": "karura".to_string(),
-                    "protocol": "taiga".to_string(),
-                    "farmType": models::FarmType::StableAmm.to_string(),
-                    "farmImpl": models::FarmImplementation::Pallet.to_string(),
-                    "router": "".to_string(),
-                    "asset": {
-                        "symbol": "taiKSM".to_string(),
-                        "address": "taiKSM".to_string(),
-                        "price": 0 as f64,
-                        "logos": ["https://raw.githubusercontent.com/yield-bay/assets/main/list/taiKSM.png".to_string()],
-                        "underlyingAssets": [],
-                    },
-                    "tvl": _tai_ksm.0 as f64,
-                    "apr.reward": tai_ksm_reward_apr, // _tai_ksm.2.1 as f64 * 100.0,
-                    "apr.base": tai_ksm_base_apr, // _tai_ksm.2.0 as f64 * 100.0,
-                    "rewards": tai_ksm_rewards,
-                    "allocPoint": 1,
-                    "lastUpdatedAtUTC": timestamp.clone(),
-                }
+            log::debug!("taiKSM farm lastUpdatedAtUTC {}", timestamp.clone());
+
+            let tai_ksm_farm = models::Farm {
+                id: 0,
+                chef: "taiKSM".to_string(),
+                router: "".to_string(),
+                chain: "karura".to_string(),
+                protocol: "taiga".to_string(),
+                farm_type: models::FarmType::StableAmm.to_string(),
+                farm_impl: models::FarmImplementation::Pallet.to_string(),
+                asset: models::FarmAsset {
+                    symbol: "taiKSM".to_string(),
+                    address: "taiKSM".to_string(),
+                    price: 0 as f64,
+                    logos: vec![
+                        "https://raw.githubusercontent.com/yield-bay/assets/main/list/taiKSM.png"
+                            .to_string(),
+                    ],
+                    underlying_assets: vec![],
+                },
+                tvl: _tai_ksm.0 as f64,
+                apr: models::APR {
+                    reward: tai_ksm_reward_apr,
+                    base: tai_ksm_base_apr,
+                },
+                rewards: tai_ksm_rewards,
+                alloc_point: 1,
+                last_updated_at_utc: timestamp.clone(),
+                ..Default::default()
             };
-            let options = FindOneAndUpdateOptions::builder()
-                .upsert(Some(true))
-                .build();
-            farms_collection
-                .find_one_and_update(tai_ksm_ff, tai_ksm_fu, Some(options))
-                .await?;
+            store.upsert_farm(&tai_ksm_farm).await?;
         } else {
-            println!("tksmf");
+            log::debug!("tksmf");
         }
     }
 
@@ -123,56 +104,49 @@ pub async fn tapio_taiga_jobs(mongo_uri: String) -> Result<(), Box<dyn std::erro
         if _3usd.0 != 0.0 {
             let mut _3usd_rewards = vec![];
             for r in _3usd.1.clone() {
-                _3usd_rewards.push(bson!({
-                    "amount": r.0 as f64,
-                    "asset":  r.1.clone(),
-                    "valueUSD": r.2 as f64,
-                    "freq": r.3.clone(),
-                }));
+                _3usd_rewards.push(models::Reward {
+                    amount: r.0 as f64,
+                    asset: r.1.clone(),
+                    value_usd: r.2 as f64,
+                    freq: r.3.clone(),
+                });
             }
 
             let timestamp = Utc::now().to_string();
 
-            println!("3USD farm lastUpdatedAtUTC {}", timestamp.clone());
-
-            let _3usd_ff = doc! {
-                "id": 1,
-                "chef": "3USD".to_string(),
-                "chain": "karura".to_string(),
-                "protocol": "taiga".to_string(),
-            };
-            let _3usd_fu = doc! {
-                "$set" : {
-                    "id": 1,
-                    "chef": "3USD".to_string(),
-                    "chain": "karura".to_string(),
-                    "protocol": "taiga".to_string(),
-                    "farmType": models::FarmType::StableAmm.to_string(),
-                    "farmImpl": models::FarmImplementation::Pallet.to_string(),
-                    "router": "".to_string(),
-                    "asset": {
-                        "symbol": "3USD".to_string(),
-                        "address": "3USD".to_string(),
-                        "price": 0 as f64,
-                        "logos": ["https://raw.githubusercontent.com/yield-bay/assets/main/list/3USD.png".to_string()],
-                        "underlyingAssets": [],
-                    },
-                    "tvl": _3usd.0 as f64,
-                    "apr.reward": _3usd_reward_apr, // _3usd.2.1 as f64 * 100.0,
-                    "apr.base": _3usd_base_apr, // _3usd.2.0 as f64 * 100.0,
-                    "rewards": _3usd_rewards,
-                    "allocPoint": 1,
-                    "lastUpdatedAtUTC": timestamp.clone(),
-                }
+            log::debug!("3USD farm lastUpdatedAtUTC {}", timestamp.clone());
+
+            let _3usd_farm = models::Farm {
+                id: 1,
+                chef: "3USD".to_string(),
+                router: "".to_string(),
+                chain: "karura".to_string(),
+                protocol: "taiga".to_string(),
+                farm_type: models::FarmType::StableAmm.to_string(),
+                farm_impl: models::FarmImplementation::Pallet.to_string(),
+                asset: models::FarmAsset {
+                    symbol: "3USD".to_string(),
+                    address: "3USD".to_string(),
+                    price: 0 as f64,
+                    logos: vec![
+                        "https://raw.githubusercontent.com/yield-bay/assets/main/list/3USD.png"
+                            .to_string(),
+                    ],
+                    underlying_assets: vec![],
+                },
+                tvl: _3usd.0 as f64,
+                apr: models::APR {
+                    reward: _3usd_reward_apr,
+                    base: _3usd_base_apr,
+                },
+                rewards: _3usd_rewards,
+                alloc_point: 1,
+                last_updated_at_utc: timestamp.clone(),
+                ..Default::default()
             };
-            let options = FindOneAndUpdateOptions::builder()
-                .upsert(Some(true))
-                .build();
-            farms_collection
-                .find_one_and_update(_3usd_ff, _3usd_fu, Some(options))
-                .await?;
+            store.upsert_farm(&_3usd_farm).await?;
         } else {
-            println!("3usdf");
+            log::debug!("3usdf");
         }
     }
 
@@ -187,48 +161,41 @@ pub async fn tapio_taiga_jobs(mongo_uri: String) -> Result<(), Box<dyn std::erro
         .await
         .unwrap();
 
-        let t_dot_rewards: Vec<Bson> = vec![];
+        let t_dot_rewards: Vec<models::Reward> = vec![];
 
         let timestamp = Utc::now().to_string();
 
-        println!("tDOT farm lastUpdatedAtUTC {}", timestamp.clone());
-
-        let t_dot_ff = doc! {
-            "id": 0,
-            "chef": "tDOT".to_string(),
-            "chain": "acala".to_string(),
-            "protocol": "tapio".to_string(),
-        };
-        let t_dot_fu = doc! {
-            "$set" : {
-                "id": 0,
-                "chef": "tDOT".to_string(),
-                "chain": "acala".to_string(),
-                "protocol": "tapio".to_string(),
-                "farmType": models::FarmType::StableAmm.to_string(),
-                "farmImpl": models::FarmImplementation::Pallet.to_string(),
-                "router": "".to_string(),
-                "asset": {
-                    "symbol": "tDOT".to_string(),
-                    "address": "tDOT".to_string(),
-                    "price": 0 as f64,
-                    "logos": ["https://raw.githubusercontent.com/yield-bay/assets/main/list/tDOT.png".to_string()],
-                    "underlyingAssets": [],
-                },
-                "tvl": _t_dot.0 as f64,
-                "apr.reward": t_dot_reward_apr, // _3usd.2.1 as f64 * 100.0,
-                "apr.base": t_dot_base_apr, // _3usd.2.0 as f64 * 100.0,
-                "rewards": t_dot_rewards,
-                "allocPoint": 1,
-                "lastUpdatedAtUTC": timestamp.clone(),
-            }
+        log::debug!("tDOT farm lastUpdatedAtUTC {}", timestamp.clone());
+
+        let t_dot_farm = models::Farm {
+            id: 0,
+            chef: "tDOT".to_string(),
+            router: "".to_string(),
+            chain: "acala".to_string(),
+            protocol: "tapio".to_string(),
+            farm_type: models::FarmType::StableAmm.to_string(),
+            farm_impl: models::FarmImplementation::Pallet.to_string(),
+            asset: models::FarmAsset {
+                symbol: "tDOT".to_string(),
+                address: "tDOT".to_string(),
+                price: 0 as f64,
+                logos: vec![
+                    "https://raw.githubusercontent.com/yield-bay/assets/main/list/tDOT.png"
+                        .to_string(),
+                ],
+                underlying_assets: vec![],
+            },
+            tvl: _t_dot.0 as f64,
+            apr: models::APR {
+                reward: t_dot_reward_apr,
+                base: t_dot_base_apr,
+            },
+            rewards: t_dot_rewards,
+            alloc_point: 1,
+            last_updated_at_utc: timestamp.clone(),
+            ..Default::default()
         };
-        let options = FindOneAndUpdateOptions::builder()
-            .upsert(Some(true))
-            .build();
-        farms_collection
-            .find_one_and_update(t_dot_ff, t_dot_fu, Some(options))
-            .await?;
+        store.upsert_farm(&t_dot_farm).await?;
     }
 
     Ok(())
@@ -359,7 +326,7 @@ async fn fetch_t_dot(
     let rewards: Vec<(i32, String, f64, String)> = vec![];
 
     if pool_data.is_ok() {
-        println!(
+        log::debug!(
             "tapio pool_datau {:?}",
             pool_data.clone().unwrap().daily_data.nodes.len()
         );
@@ -372,7 +339,7 @@ async fn fetch_t_dot(
         // )
         // .await;
 
-        // println!("t_dot_price_history {:?}", t_dot_price_history);
+        // log::debug!("t_dot_price_history {:?}", t_dot_price_history);
 
         // let mut t_dot_price = 0.0;
         // if t_dot_price_history.len() > 0 {
@@ -384,7 +351,7 @@ async fn fetch_t_dot(
         .await?
         .json::<apis::coingecko::Root>()
         .await?;
-        println!("DPPP {:?}", dot_price.polkadot.usd);
+        log::debug!("DPPP {:?}", dot_price.polkadot.usd);
 
         let t_dot_price = dot_price.polkadot.usd;
 
@@ -398,7 +365,7 @@ async fn fetch_t_dot(
                 .unwrap()
                 .total_supply;
         }
-        println!(
+        log::debug!(
             "current_supply {:?}, pdau {:?}, t_dot_price {:?}",
             current_supply,
             pool_data.clone().unwrap(),
@@ -409,7 +376,7 @@ async fn fetch_t_dot(
 
         // apr = fetch_tai_ksm_apr(pool_data.clone().unwrap(), acala_dex_query_str.clone()).await;
     } else {
-        println!("pooldatau notok");
+        log::debug!("pooldatau notok");
     }
 
     Ok((tvl, rewards, apr))
@@ -438,7 +405,7 @@ async fn fetch_tai_ksm(
     let mut rewards = vec![];
 
     if pool_data.is_ok() {
-        println!(
+        log::debug!(
             "pool_datau {:?}",
             pool_data.clone().unwrap().daily_data.nodes.len()
         );
@@ -649,9 +616,10 @@ async fn get_token_price_history(
         .query_with_vars_unwrap::<subgraph::KaruraTokenPriceHistoryData, Vars>(&query_str, vars)
         .await;
 
-    println!(
+    log::debug!(
         "protocol {:?} price_history_data {:?}",
-        protocol, price_history_data
+        protocol,
+        price_history_data
     );
 
     let ph = price_history_data
@@ -0,0 +1,193 @@
+use std::sync::Arc;
+
+use ethers::prelude::{Address, U256};
+use futures::future::LocalBoxFuture;
+
+use crate::constants;
+use crate::contracts;
+use crate::multicall;
+use crate::store;
+
+/// How far a stored LP price may be from the pair's fair price before it's replaced.
+pub const MAX_LP_PRICE_DEVIATION: f64 = 0.1;
+
+/// How many base pools deep a stable pool's tokens are followed, a metapool's base pool LP
+/// being priced as a stable LP itself.
+const MAX_STABLE_POOL_DEPTH: usize = 2;
+
+/// Prices of a uniswap v2 style LP token, from the pair's reserves and the prices of its
+/// tokens in `assets`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct V2LpPrice {
+    /// Value of the reserves at the tokens' prices over the supply.
+    pub spot: f64,
+    /// Fair-reserves price, `2 * sqrt(r0 * r1) * sqrt(p0 * p1) / supply`. Unlike the spot
+    /// price it can't be moved by skewing the reserves within a block.
+    pub fair: f64,
+    pub total_supply: f64,
+}
+
+impl V2LpPrice {
+    /// Relative difference between `price` and the fair price.
+    pub fn deviation(&self, price: f64) -> f64 {
+        if self.fair == 0.0 {
+            return 0.0;
+        }
+        (price - self.fair).abs() / self.fair
+    }
+}
+
+pub async fn v2_lp_price(
+    calls: &multicall::Multicall,
+    store: &dyn store::Store,
+    chain: &str,
+    protocol: &str,
+    pair: &str,
+) -> Result<V2LpPrice, Box<dyn std::error::Error>> {
+    let lp = contracts::ILpToken::new(pair.parse::<Address>()?, Arc::clone(calls.client()));
+    let token0 = ethers::utils::to_checksum(&calls.call(lp.token_0()).await?, None);
+    let token1 = ethers::utils::to_checksum(&calls.call(lp.token_1()).await?, None);
+    let (r0, r1, _): (u128, u128, u32) = calls.call(lp.get_reserves()).await?;
+    let total_supply: U256 = calls.call(lp.total_supply()).await?;
+
+    let asset0 = store
+        .find_asset(&token0, chain, protocol)
+        .await?
+        .ok_or_else(|| format!("{} of pair {} isn't an asset", token0, pair))?;
+    let asset1 = store
+        .find_asset(&token1, chain, protocol)
+        .await?
+        .ok_or_else(|| format!("{} of pair {} isn't an asset", token1, pair))?;
+    if asset0.price == 0.0 || asset1.price == 0.0 {
+        return Err(format!("tokens of pair {} aren't priced", pair).into());
+    }
+
+    let reserve0 = r0 as f64 / constants::utils::TEN_F64.powf(asset0.decimals as f64);
+    let reserve1 = r1 as f64 / constants::utils::TEN_F64.powf(asset1.decimals as f64);
+    let total_supply = total_supply.as_u128() as f64 / constants::utils::TEN_F64.powf(18.0);
+    if total_supply == 0.0 {
+        return Err(format!("pair {} has no supply", pair).into());
+    }
+
+    let spot = (reserve0 * asset0.price + reserve1 * asset1.price) / total_supply;
+    let fair =
+        2.0 * (reserve0 * reserve1).sqrt() * (asset0.price * asset1.price).sqrt() / total_supply;
+
+    Ok(V2LpPrice {
+        spot,
+        fair,
+        total_supply,
+    })
+}
+
+/// Price of a stable-swap (saddle style) LP token, from the pool's virtual price and the
+/// prices of its tokens in `assets`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StableLpPrice {
+    /// `getVirtualPrice`, what an LP token is worth in units of the pool's tokens.
+    pub virtual_price: f64,
+    /// Virtual price times the lowest token price, the LP is never worth more than a share of
+    /// the pool redeemed for its cheapest token.
+    pub price: f64,
+    /// Virtual price times the token prices weighted by the pool's balances.
+    pub weighted_price: f64,
+    pub total_supply: f64,
+    /// `price` times the supply.
+    pub liquidity: f64,
+}
+
+/// Prices the stable-swap LP token at `lp` from its owner, the pool's swap contract.
+/// Balances are scaled to 18 decimals with the pool's precision multipliers, and a token
+/// that's itself the LP of a base pool (a metapool) is priced from the base pool.
+pub async fn stable_lp_price(
+    calls: &multicall::Multicall,
+    store: &dyn store::Store,
+    chain: &str,
+    protocol: &str,
+    lp: Address,
+) -> Result<StableLpPrice, Box<dyn std::error::Error>> {
+    stable_lp_price_at(calls, store, chain, protocol, lp, 0).await
+}
+
+fn stable_lp_price_at<'a>(
+    calls: &'a multicall::Multicall,
+    store: &'a dyn store::Store,
+    chain: &'a str,
+    protocol: &'a str,
+    lp: Address,
+    depth: usize,
+) -> LocalBoxFuture<'a, Result<StableLpPrice, Box<dyn std::error::Error>>> {
+    Box::pin(async move {
+        let lp_token = contracts::IStableLpToken::new(lp, Arc::clone(calls.client()));
+        let owner = contracts::IStableLpTokenOwner::new(
+            calls.call(lp_token.owner()).await?,
+            Arc::clone(calls.client()),
+        );
+        let tokens: Vec<Address> = calls.call(owner.get_tokens()).await?;
+        let balances: Vec<U256> = calls.call(owner.get_token_balances()).await?;
+        let multipliers: Vec<U256> = calls.call(owner.get_token_precision_multipliers()).await?;
+        let virtual_price: U256 = calls.call(owner.get_virtual_price()).await?;
+        let total_supply: U256 = calls.call(lp_token.total_supply()).await?;
+        if tokens.len() != balances.len() || tokens.len() != multipliers.len() {
+            return Err(format!("stable pool of {:?} returned mismatched token lists", lp).into());
+        }
+
+        let mut min_price = f64::MAX;
+        let mut value = 0.0;
+        let mut amount = 0.0;
+        for ((token, balance), multiplier) in tokens.iter().zip(&balances).zip(&multipliers) {
+            let token_price =
+                stable_token_price(calls, store, chain, protocol, *token, depth).await?;
+            let normalized =
+                (*balance * *multiplier).as_u128() as f64 / constants::utils::TEN_F64.powf(18.0);
+            min_price = min_price.min(token_price);
+            value += normalized * token_price;
+            amount += normalized;
+        }
+        if tokens.is_empty() || amount == 0.0 {
+            return Err(format!("stable pool of {:?} has no balances", lp).into());
+        }
+
+        let virtual_price = virtual_price.as_u128() as f64 / constants::utils::TEN_F64.powf(18.0);
+        let total_supply = total_supply.as_u128() as f64 / constants::utils::TEN_F64.powf(18.0);
+        let price = virtual_price * min_price;
+
+        Ok(StableLpPrice {
+            virtual_price,
+            price,
+            weighted_price: virtual_price * value / amount,
+            total_supply,
+            liquidity: price * total_supply,
+        })
+    })
+}
+
+/// Price of one of a stable pool's tokens, from its asset or, for a base pool's LP, from
+/// the base pool.
+async fn stable_token_price(
+    calls: &multicall::Multicall,
+    store: &dyn store::Store,
+    chain: &str,
+    protocol: &str,
+    token: Address,
+    depth: usize,
+) -> Result<f64, Box<dyn std::error::Error>> {
+    let address = ethers::utils::to_checksum(&token, None);
+    let asset = store.find_asset(&address, chain, protocol).await?;
+    if let Some(asset) = asset.as_ref().filter(|a| !a.is_lp && a.price > 0.0) {
+        return Ok(asset.price);
+    }
+
+    if depth < MAX_STABLE_POOL_DEPTH {
+        match stable_lp_price_at(calls, store, chain, protocol, token, depth + 1).await {
+            Ok(base) => return Ok(base.price),
+            Err(e) => log::warn!("{} isn't a priced stable pool LP: {}", address, e),
+        }
+    }
+
+    // an LP asset written by an earlier run is still better than nothing
+    match asset.filter(|a| a.price > 0.0) {
+        Some(asset) => Ok(asset.price),
+        None => Err(format!("{} isn't a priced asset", address).into()),
+    }
+}
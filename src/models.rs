@@ -1,3 +1,4 @@
+use mongodb::bson;
 use serde::{Deserialize, Serialize};
 
 use std::fmt;
@@ -13,6 +14,13 @@ pub struct Asset {
     pub decimals: u32,
     pub logos: Vec<String>,
     pub price: f64,
+    #[serde(default)]
+    pub price_source: PriceSource,
+    #[serde(default)]
+    pub price_confidence: PriceConfidence,
+    /// Set on stables, null for everything else.
+    #[serde(default)]
+    pub peg: Option<Peg>,
     pub liquidity: f64,
     pub total_supply: f64,
     #[serde(rename = "isLP")]
@@ -25,7 +33,30 @@ pub struct Asset {
     pub last_updated_at_utc: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+impl Asset {
+    pub fn snapshot(&self, timestamp: bson::DateTime) -> AssetSnapshot {
+        AssetSnapshot {
+            address: self.address.clone(),
+            chain: self.chain.clone(),
+            protocol: self.protocol.clone(),
+            timestamp,
+            price: self.price,
+            liquidity: self.liquidity,
+            total_supply: self.total_supply,
+        }
+    }
+
+    /// The asset as listed in an LP's or farm's `underlyingAssets`.
+    pub fn underlying(&self) -> UnderlyingAsset {
+        UnderlyingAsset {
+            symbol: self.symbol.clone(),
+            address: self.address.clone(),
+            decimals: self.decimals,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Farm {
     pub id: i32,
@@ -42,13 +73,127 @@ pub struct Farm {
     pub alloc_point: u32,
     #[serde(rename = "lastUpdatedAtUTC")]
     pub last_updated_at_utc: String,
+    /// Block the farm's contract reads were made at, unset for farms not read from a chef.
+    #[serde(default)]
+    pub block: Option<u64>,
+    /// Fees and lockups depositors are subject to, all zero where the source has none.
+    #[serde(default)]
+    pub terms: FarmTerms,
+    /// Only written by the yields job, see `apr::farm_yields`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub yields: Option<Yields>,
+    // Scores are only written by the scoring job, other jobs leave them untouched.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub total_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub tvl_score: Option<f64>,
-    #[serde(rename = "baseAPRScore")]
+    #[serde(rename = "baseAPRScore", skip_serializing_if = "Option::is_none")]
     pub base_apr_score: Option<f64>,
-    #[serde(rename = "rewardAPRScore")]
+    #[serde(rename = "rewardAPRScore", skip_serializing_if = "Option::is_none")]
     pub reward_apr_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub rewards_score: Option<f64>,
+    // Risk factor scores, see `risk::RiskScores`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tvl_trend_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reward_volatility_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peg_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impermanent_loss_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audit_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub implementation_score: Option<f64>,
+    /// `version` of the scoring config the scores were computed with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_version: Option<String>,
+    /// Reason of the scoring rule that kept the farm from being scored, in which case it
+    /// has no scores.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score_exclusion: Option<String>,
+}
+
+impl Farm {
+    /// Unsets every score, for farms the scoring rules exclude.
+    pub fn clear_scores(&mut self) {
+        self.total_score = None;
+        self.tvl_score = None;
+        self.base_apr_score = None;
+        self.reward_apr_score = None;
+        self.rewards_score = None;
+        self.age_score = None;
+        self.tvl_trend_score = None;
+        self.reward_volatility_score = None;
+        self.peg_score = None;
+        self.impermanent_loss_score = None;
+        self.audit_score = None;
+        self.implementation_score = None;
+    }
+
+    /// USD value of the farm's rewards per day, whatever their frequency.
+    pub fn daily_rewards_usd(&self) -> f64 {
+        self.rewards
+            .iter()
+            .map(|x| {
+                if x.freq == "Weekly" {
+                    return x.value_usd / 7.0;
+                } else if x.freq == "Monthly" {
+                    return x.value_usd / 30.0;
+                } else if x.freq == "Annually" {
+                    return x.value_usd / 365.0;
+                }
+                return x.value_usd;
+            })
+            .sum()
+    }
+
+    pub fn snapshot(&self, timestamp: bson::DateTime) -> FarmSnapshot {
+        FarmSnapshot {
+            id: self.id,
+            chef: self.chef.clone(),
+            chain: self.chain.clone(),
+            protocol: self.protocol.clone(),
+            asset_address: self.asset.address.clone(),
+            timestamp,
+            tvl: self.tvl,
+            apr: self.apr,
+            rewards_usd: self.daily_rewards_usd(),
+            block: self.block,
+            total_score: self.total_score,
+            tvl_score: self.tvl_score,
+            base_apr_score: self.base_apr_score,
+            reward_apr_score: self.reward_apr_score,
+            rewards_score: self.rewards_score,
+            age_score: self.age_score,
+            tvl_trend_score: self.tvl_trend_score,
+            reward_volatility_score: self.reward_volatility_score,
+            peg_score: self.peg_score,
+            impermanent_loss_score: self.impermanent_loss_score,
+            audit_score: self.audit_score,
+            implementation_score: self.implementation_score,
+            score_version: self.score_version.clone(),
+        }
+    }
+}
+
+/// What entering and leaving a farm costs, and how long deposits and rewards are held.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmTerms {
+    /// Fraction of a deposit taken as a fee, e.g. 0.04 for a 4% (400 bp) fee.
+    pub deposit_fee: f64,
+    /// Fraction of a withdrawal taken as a fee.
+    pub withdrawal_fee: f64,
+    /// Seconds between harvests of the farm's rewards.
+    pub harvest_interval: u64,
+    /// Seconds a deposit is locked for before it can be withdrawn.
+    pub lockup: u64,
+    /// Seconds harvested rewards vest over, unset when they're paid out at once.
+    pub vesting: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -59,7 +204,7 @@ pub struct UnderlyingAsset {
     pub decimals: u32,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FarmAsset {
     pub symbol: String,
@@ -76,6 +221,42 @@ pub struct APR {
     pub base: f64,
 }
 
+/// How often rewards are harvested and deposited back into a farm.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Compounding {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl Compounding {
+    pub fn periods_per_year(&self) -> f64 {
+        match self {
+            Compounding::Daily => 365.0,
+            Compounding::Weekly => 52.0,
+        }
+    }
+}
+
+/// Yields derived from a farm's `APR` and `terms`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Yields {
+    /// `apr.base` plus `apr.reward`.
+    #[serde(rename = "totalAPR")]
+    pub total_apr: f64,
+    /// The total APR compounded `compounding`. Vested rewards can't be deposited back
+    /// when they're earned, so their APR is added on uncompounded.
+    #[serde(rename = "APY")]
+    pub apy: f64,
+    pub compounding: Compounding,
+    /// The total APR less the deposit and withdrawal fees, spread over `holdingDays`.
+    #[serde(rename = "netAPR")]
+    pub net_apr: f64,
+    pub holding_days: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Reward {
@@ -86,6 +267,72 @@ pub struct Reward {
     pub freq: String,
 }
 
+/// ERC20 metadata read from the token contract, cached per chain and address in `tokens`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Token {
+    pub address: String,
+    pub chain: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u32,
+}
+
+/// A farm as of one run, appended to `farm_snapshots`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FarmSnapshot {
+    pub id: i32,
+    pub chef: String,
+    pub chain: String,
+    pub protocol: String,
+    pub asset_address: String,
+    pub timestamp: bson::DateTime,
+    pub tvl: f64,
+    pub apr: APR,
+    /// Daily rewards in USD.
+    #[serde(rename = "rewardsUSD")]
+    pub rewards_usd: f64,
+    #[serde(default)]
+    pub block: Option<u64>,
+    pub total_score: Option<f64>,
+    pub tvl_score: Option<f64>,
+    #[serde(rename = "baseAPRScore")]
+    pub base_apr_score: Option<f64>,
+    #[serde(rename = "rewardAPRScore")]
+    pub reward_apr_score: Option<f64>,
+    pub rewards_score: Option<f64>,
+    #[serde(default)]
+    pub age_score: Option<f64>,
+    #[serde(default)]
+    pub tvl_trend_score: Option<f64>,
+    #[serde(default)]
+    pub reward_volatility_score: Option<f64>,
+    #[serde(default)]
+    pub peg_score: Option<f64>,
+    #[serde(default)]
+    pub impermanent_loss_score: Option<f64>,
+    #[serde(default)]
+    pub audit_score: Option<f64>,
+    #[serde(default)]
+    pub implementation_score: Option<f64>,
+    #[serde(default)]
+    pub score_version: Option<String>,
+}
+
+/// An asset as of one run, appended to `asset_snapshots`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetSnapshot {
+    pub address: String,
+    pub chain: String,
+    pub protocol: String,
+    pub timestamp: bson::DateTime,
+    pub price: f64,
+    pub liquidity: f64,
+    pub total_supply: f64,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum FarmType {
@@ -113,6 +360,72 @@ pub enum Freq {
     Annually,
 }
 
+/// Where an asset's price came from.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PriceSource {
+    /// The dex subgraph, `derivedETH * ethPrice` for tokens and `reserveUSD / totalSupply` for pairs.
+    Subgraph,
+    /// Pool reserves read on-chain.
+    DexReserves,
+    Coingecko,
+    GeckoTerminal,
+    DexScreener,
+    /// The protocol's own api, e.g. curve's lp token price.
+    Protocol,
+    /// Another asset's price, e.g. stKSM at xcKSM's.
+    Derived,
+    /// A stable's peg from the price feeds in the registry.
+    Pegged,
+    #[default]
+    Unknown,
+}
+
+impl PriceSource {
+    /// How far a price from this source can be trusted when it's the first one tried.
+    pub fn confidence(&self) -> PriceConfidence {
+        match self {
+            PriceSource::Subgraph
+            | PriceSource::DexReserves
+            | PriceSource::Coingecko
+            | PriceSource::Protocol => PriceConfidence::High,
+            PriceSource::GeckoTerminal | PriceSource::DexScreener | PriceSource::Derived => {
+                PriceConfidence::Medium
+            }
+            PriceSource::Pegged | PriceSource::Unknown => PriceConfidence::Low,
+        }
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PriceConfidence {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl PriceConfidence {
+    pub fn lower(&self) -> PriceConfidence {
+        match self {
+            PriceConfidence::High => PriceConfidence::Medium,
+            _ => PriceConfidence::Low,
+        }
+    }
+}
+
+/// A stable's peg checked against its market price. Once the market is off by more
+/// than the feed's threshold the stable is `depegged` and priced at the market.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Peg {
+    pub peg: f64,
+    pub market_price: f64,
+    pub deviation: f64,
+    pub depegged: bool,
+}
+
 impl fmt::Display for FarmType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
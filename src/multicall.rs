@@ -0,0 +1,124 @@
+use std::{collections::HashMap, sync::Arc, sync::Mutex};
+
+use ethers::{
+    abi::Detokenize,
+    contract::ContractCall,
+    prelude::{Address, BlockNumber, Bytes, NameOrAddress},
+};
+
+use crate::chef::{ChainClient, ChainMiddleware};
+use crate::contracts;
+
+/// Multicall3, deployed at the same address on every chain we run against.
+pub const MULTICALL3: &str = "0xcA11bde05977b3631167028862bE2a173976CA11";
+
+/// Calls sent in one `aggregate3`, kept well under what the public rpcs accept.
+pub const MAX_CALLS: usize = 100;
+
+/// A view call by its target and calldata.
+pub type Call = (Address, Vec<u8>);
+
+/// Batches view calls through Multicall3 and keeps what they returned, so reads that
+/// were prefetched don't go over the wire again.
+///
+/// Callers go through `call`, which decodes a prefetched result when there is one and
+/// makes the call directly otherwise. Without a multicall address every call is direct.
+/// Every call, batched or not, is made at `block` when it's set.
+pub struct Multicall {
+    client: ChainClient,
+    contract: Option<contracts::IMulticall3<ChainMiddleware>>,
+    block: Option<u64>,
+    results: Mutex<HashMap<Call, Bytes>>,
+}
+
+impl Multicall {
+    pub fn new(client: ChainClient, address: Option<Address>, block: Option<u64>) -> Self {
+        Multicall {
+            contract: address.map(|a| contracts::IMulticall3::new(a, Arc::clone(&client))),
+            client,
+            block,
+            results: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn client(&self) -> &ChainClient {
+        &self.client
+    }
+
+    fn pinned<D: Detokenize>(
+        &self,
+        call: ContractCall<ChainMiddleware, D>,
+    ) -> ContractCall<ChainMiddleware, D> {
+        match self.block {
+            Some(block) => call.block(BlockNumber::Number(block.into())),
+            None => call,
+        }
+    }
+
+    /// Runs `calls` in batches of `MAX_CALLS` and keeps the results of the ones that
+    /// succeeded. Calls that revert are left to fail when they're made directly.
+    pub async fn prefetch(&self, calls: Vec<Call>) -> Result<(), Box<dyn std::error::Error>> {
+        let contract = match &self.contract {
+            Some(contract) => contract,
+            None => return Ok(()),
+        };
+
+        let mut pending: Vec<Call> = vec![];
+        {
+            let results = self.results.lock().unwrap();
+            for call in calls {
+                if !results.contains_key(&call) && !pending.contains(&call) {
+                    pending.push(call);
+                }
+            }
+        }
+
+        for batch in pending.chunks(MAX_CALLS) {
+            let aggregate = batch
+                .iter()
+                .map(|(target, data)| contracts::Call3 {
+                    target: *target,
+                    allow_failure: true,
+                    call_data: Bytes::from(data.clone()),
+                })
+                .collect();
+            let returned = self.pinned(contract.aggregate_3(aggregate)).call().await?;
+
+            let mut results = self.results.lock().unwrap();
+            for (call, (success, data)) in batch.iter().zip(returned) {
+                if success {
+                    results.insert(call.clone(), data);
+                }
+            }
+        }
+        log::debug!("multicall prefetched {} calls", pending.len());
+
+        Ok(())
+    }
+
+    /// The call's prefetched result, without falling back to making it.
+    pub fn cached<D: Detokenize>(&self, call: &ContractCall<ChainMiddleware, D>) -> Option<D> {
+        let data = self.results.lock().unwrap().get(&key(call)?).cloned()?;
+        D::from_tokens(call.function.decode_output(&data).ok()?).ok()
+    }
+
+    /// The call's result, decoded from a prefetch when there is one.
+    pub async fn call<D: Detokenize>(
+        &self,
+        call: ContractCall<ChainMiddleware, D>,
+    ) -> Result<D, Box<dyn std::error::Error>> {
+        match self.cached(&call) {
+            Some(result) => Ok(result),
+            None => Ok(self.pinned(call).call().await?),
+        }
+    }
+}
+
+/// The target and calldata of `call`, for `Multicall::prefetch`.
+pub fn key<D: Detokenize>(call: &ContractCall<ChainMiddleware, D>) -> Option<Call> {
+    let target = match call.tx.to()? {
+        NameOrAddress::Address(address) => *address,
+        NameOrAddress::Name(_) => return None,
+    };
+    Some((target, call.tx.data()?.to_vec()))
+}
@@ -0,0 +1,402 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use ethers::{prelude::Address, utils::to_checksum};
+use futures::future::LocalBoxFuture;
+
+use crate::apis;
+use crate::chef::ChainClient;
+use crate::config;
+use crate::constants;
+use crate::contracts;
+use crate::models::{self, PriceConfidence, PriceSource};
+use crate::multicall;
+use crate::tokens;
+
+/// Order sources are tried in when a feed doesn't list its own.
+pub const DEFAULT_SOURCES: [PriceSource; 5] = [
+    PriceSource::Subgraph,
+    PriceSource::DexReserves,
+    PriceSource::Coingecko,
+    PriceSource::GeckoTerminal,
+    PriceSource::DexScreener,
+];
+
+/// Sources a stable's market price is checked against its peg with.
+const MARKET_SOURCES: [PriceSource; 2] = [PriceSource::Subgraph, PriceSource::DexReserves];
+
+/// A usd price along with the source it came from, and the peg check for stables.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Price {
+    pub usd: f64,
+    pub source: PriceSource,
+    pub confidence: PriceConfidence,
+    pub peg: Option<models::Peg>,
+}
+
+impl Price {
+    pub fn new(usd: f64, source: PriceSource) -> Self {
+        Price {
+            usd,
+            source,
+            confidence: source.confidence(),
+            peg: None,
+        }
+    }
+
+    /// No source had a price.
+    pub fn unknown() -> Self {
+        Price::new(0.0, PriceSource::Unknown)
+    }
+}
+
+/// Prices a token from an ordered list of sources, falling back to the next one when a
+/// source fails or has no price. A price that needed a fallback gets a lower confidence.
+///
+/// Quotes are kept for the run, so the subgraph prices seen by the subgraph jobs are
+/// what the chef jobs get later on.
+pub struct PriceOracle {
+    feeds: Vec<config::PriceFeed>,
+    clients: HashMap<String, ChainClient>,
+    /// Block of each chain the run's reads are pinned to, see `chef::blocks`.
+    blocks: HashMap<String, u64>,
+    tokens: Arc<tokens::TokenResolver>,
+    quotes: Mutex<HashMap<(PriceSource, String, String), f64>>,
+}
+
+impl PriceOracle {
+    pub fn new(
+        feeds: Vec<config::PriceFeed>,
+        clients: HashMap<String, ChainClient>,
+        blocks: HashMap<String, u64>,
+        tokens: Arc<tokens::TokenResolver>,
+    ) -> Self {
+        PriceOracle {
+            feeds,
+            clients,
+            blocks,
+            tokens,
+            quotes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Price of the token at `address` on `chain`, `subgraph` being what the dex subgraph
+    /// being indexed quotes for it.
+    pub async fn price(
+        &self,
+        chain: &str,
+        address: &str,
+        subgraph: Option<f64>,
+    ) -> Result<Price, Box<dyn std::error::Error>> {
+        self.price_at(chain, address, subgraph, 0).await
+    }
+
+    /// Like `price`, but a token no source can price is written with `Price::unknown`
+    /// rather than failing the subgraph it came from.
+    pub async fn subgraph_price(&self, chain: &str, address: &str, subgraph: f64) -> Price {
+        match self.price(chain, address, Some(subgraph)).await {
+            Ok(price) => price,
+            Err(e) => {
+                log::debug!("{}", e);
+                Price::unknown()
+            }
+        }
+    }
+
+    fn price_at<'a>(
+        &'a self,
+        chain: &'a str,
+        address: &'a str,
+        subgraph: Option<f64>,
+        depth: usize,
+    ) -> LocalBoxFuture<'a, Result<Price, Box<dyn std::error::Error>>> {
+        Box::pin(async move {
+            let address = to_checksum(&address.parse::<Address>()?, None);
+            if let Some(usd) = subgraph.filter(|usd| *usd > 0.0) {
+                self.quotes.lock().unwrap().insert(
+                    (PriceSource::Subgraph, chain.to_string(), address.clone()),
+                    usd,
+                );
+            }
+
+            let feed = self.feed(chain, &address).await;
+            if let Some(feed) = feed.filter(|f| f.peg.is_some()) {
+                return Ok(self
+                    .stable_price(chain, &address, feed, subgraph, depth)
+                    .await);
+            }
+
+            let sources = feed
+                .and_then(|f| f.sources.clone())
+                .unwrap_or_else(|| DEFAULT_SOURCES.to_vec());
+
+            let mut fell_back = false;
+            for source in sources {
+                match self
+                    .quote(source, chain, &address, feed, subgraph, depth)
+                    .await
+                {
+                    Ok(Some(usd)) if usd > 0.0 => {
+                        let mut price = Price::new(usd, source);
+                        if fell_back {
+                            price.confidence = price.confidence.lower();
+                        }
+                        return Ok(price);
+                    }
+                    // the source applies to the token but had no price for it
+                    Ok(Some(_)) => fell_back = true,
+                    Ok(None) => {}
+                    Err(e) => {
+                        log::warn!(
+                            "{:?} price of {} on {} failed: {}",
+                            source,
+                            address,
+                            chain,
+                            e
+                        );
+                        fell_back = true;
+                    }
+                }
+            }
+
+            Err(format!("no price for {} on {}", address, chain).into())
+        })
+    }
+
+    /// The source's price of the token, `None` when the source doesn't apply to it.
+    async fn quote(
+        &self,
+        source: PriceSource,
+        chain: &str,
+        address: &str,
+        feed: Option<&config::PriceFeed>,
+        subgraph: Option<f64>,
+        depth: usize,
+    ) -> Result<Option<f64>, Box<dyn std::error::Error>> {
+        let key = (source, chain.to_string(), address.to_string());
+        if source == PriceSource::Subgraph {
+            return Ok(subgraph.or_else(|| self.quotes.lock().unwrap().get(&key).copied()));
+        }
+        let feed = match feed {
+            Some(feed) => feed,
+            None => return Ok(None),
+        };
+        if let Some(usd) = self.quotes.lock().unwrap().get(&key) {
+            return Ok(Some(*usd));
+        }
+
+        let usd = match source {
+            PriceSource::DexReserves => match feed.pair.as_deref() {
+                Some(pair) => self.reserves_price(chain, address, pair, depth).await?,
+                None => return Ok(None),
+            },
+            PriceSource::Coingecko => match feed.coingecko.as_deref() {
+                Some(id) => coingecko_price(id).await?,
+                None => return Ok(None),
+            },
+            PriceSource::GeckoTerminal => match feed.geckoterminal.as_deref() {
+                Some(url) => geckoterminal_price(url).await?,
+                None => return Ok(None),
+            },
+            PriceSource::DexScreener => match feed.dexscreener.as_deref() {
+                Some(url) => dexscreener_price(url).await?,
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+
+        self.quotes.lock().unwrap().insert(key, usd);
+        Ok(Some(usd))
+    }
+
+    /// A stable is priced at its peg while its market price agrees, and at the market
+    /// price once that's off by more than the feed's threshold.
+    async fn stable_price(
+        &self,
+        chain: &str,
+        address: &str,
+        feed: &config::PriceFeed,
+        subgraph: Option<f64>,
+        depth: usize,
+    ) -> Price {
+        let peg = feed.peg.unwrap_or(1.0);
+
+        let mut market = None;
+        for source in MARKET_SOURCES {
+            match self
+                .quote(source, chain, address, Some(feed), subgraph, depth)
+                .await
+            {
+                Ok(Some(usd)) if usd > 0.0 => {
+                    market = Some((usd, source));
+                    break;
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!(
+                    "{:?} price of {} on {} failed: {}",
+                    source,
+                    address,
+                    chain,
+                    e
+                ),
+            }
+        }
+
+        // without a market price the peg can't be checked, so it's only trusted so far
+        let (market_price, source) = match market {
+            Some(market) => market,
+            None => {
+                log::warn!(
+                    "no market price for {} on {}, using its peg {}",
+                    address,
+                    chain,
+                    peg
+                );
+                return Price::new(peg, PriceSource::Pegged);
+            }
+        };
+
+        let deviation = (market_price - peg).abs() / peg;
+        let depegged = deviation > feed.depeg_threshold;
+        let mut price = if depegged {
+            log::warn!(
+                "{} on {} depegged: {} against a peg of {}",
+                address,
+                chain,
+                market_price,
+                peg
+            );
+            Price::new(market_price, source)
+        } else {
+            Price {
+                usd: peg,
+                source: PriceSource::Pegged,
+                confidence: PriceConfidence::High,
+                peg: None,
+            }
+        };
+        price.peg = Some(models::Peg {
+            peg,
+            market_price,
+            deviation,
+            depegged,
+        });
+        price
+    }
+
+    async fn feed(&self, chain: &str, address: &str) -> Option<&config::PriceFeed> {
+        let by_address = self.feeds.iter().find(|f| {
+            f.chain == chain
+                && f.address
+                    .as_deref()
+                    .map_or(false, |a| a.eq_ignore_ascii_case(address))
+        });
+        if by_address.is_some() {
+            return by_address;
+        }
+
+        // only read the token's symbol when some feed on the chain needs it
+        if !self
+            .feeds
+            .iter()
+            .any(|f| f.chain == chain && f.address.is_none())
+        {
+            return None;
+        }
+        let symbol = match self.tokens.resolve(chain, address).await {
+            Ok(token) => token.symbol,
+            Err(e) => {
+                log::warn!("couldn't resolve {} on {}: {}", address, chain, e);
+                return None;
+            }
+        };
+        self.feeds.iter().find(|f| {
+            f.chain == chain && f.address.is_none() && f.symbol.as_deref() == Some(&symbol)
+        })
+    }
+
+    /// Prices the token from its reserves in `pair` against the pair's other token, read at
+    /// the chain's pinned block.
+    async fn reserves_price(
+        &self,
+        chain: &str,
+        address: &str,
+        pair: &str,
+        depth: usize,
+    ) -> Result<f64, Box<dyn std::error::Error>> {
+        // the other token may itself be priced from a pair, don't follow those forever
+        if depth > 2 {
+            return Err(format!("{} is priced through too many pairs", address).into());
+        }
+
+        let client = self
+            .clients
+            .get(chain)
+            .ok_or_else(|| format!("no rpc client for {}", chain))?;
+        let calls =
+            multicall::Multicall::new(Arc::clone(client), None, self.blocks.get(chain).copied());
+        let lp = contracts::ILpToken::new(pair.parse::<Address>()?, Arc::clone(client));
+        let token0 = to_checksum(&calls.call(lp.token_0()).await?, None);
+        let token1 = to_checksum(&calls.call(lp.token_1()).await?, None);
+        let (r0, r1, _): (u128, u128, u32) = calls.call(lp.get_reserves()).await?;
+
+        let (reserve, other, other_reserve) = if token0 == address {
+            (r0, token1, r1)
+        } else if token1 == address {
+            (r1, token0, r0)
+        } else {
+            return Err(format!("{} is not in pair {}", address, pair).into());
+        };
+        if reserve == 0 {
+            return Ok(0.0);
+        }
+
+        let decimals = self.tokens.resolve(chain, address).await?.decimals;
+        let other_decimals = self.tokens.resolve(chain, &other).await?.decimals;
+        let other_price = self.price_at(chain, &other, None, depth + 1).await?;
+
+        let amount = reserve as f64 / constants::utils::TEN_F64.powf(decimals as f64);
+        let other_amount =
+            other_reserve as f64 / constants::utils::TEN_F64.powf(other_decimals as f64);
+        Ok(other_amount * other_price.usd / amount)
+    }
+}
+
+async fn coingecko_price(id: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    let url = format!(
+        "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies=usd",
+        id
+    );
+    let prices = reqwest::get(url)
+        .await?
+        .json::<apis::coingecko::SimplePriceRoot>()
+        .await?;
+    let price = prices
+        .get(id)
+        .ok_or_else(|| format!("coingecko has no price for {}", id))?;
+    Ok(price.usd)
+}
+
+async fn geckoterminal_price(url: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    let pool = reqwest::get(url)
+        .await?
+        .json::<apis::geckoterminal::Root>()
+        .await?;
+    Ok(pool
+        .data
+        .attributes
+        .price_in_usd
+        .unwrap_or_default()
+        .parse()
+        .unwrap_or_default())
+}
+
+async fn dexscreener_price(url: &str) -> Result<f64, Box<dyn std::error::Error>> {
+    let pairs = reqwest::get(url).await?.json::<apis::dx2::Root>().await?;
+    match pairs.pairs.get(0) {
+        Some(pair) => Ok(pair.price_usd.parse().unwrap_or_default()),
+        None => Err(format!("dexscreener has no pair at {}", url).into()),
+    }
+}
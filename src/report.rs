@@ -0,0 +1,70 @@
+use std::{fmt, sync::Mutex};
+
+/// A job, protocol or single pid that failed during a run.
+#[derive(Debug, Clone)]
+pub struct Failure {
+    pub protocol: String,
+    pub chain: Option<String>,
+    pub pid: Option<u32>,
+    pub error: String,
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.protocol)?;
+        if let Some(chain) = &self.chain {
+            write!(f, " on {}", chain)?;
+        }
+        if let Some(pid) = self.pid {
+            write!(f, " pid {}", pid)?;
+        }
+        write!(f, ": {}", self.error)
+    }
+}
+
+/// Failures collected over one run of the jobs. Failing jobs are recorded here
+/// instead of aborting the rest of the run.
+#[derive(Debug, Default)]
+pub struct Report {
+    failures: Mutex<Vec<Failure>>,
+}
+
+impl Report {
+    pub fn new() -> Self {
+        Report::default()
+    }
+
+    pub fn record(
+        &self,
+        protocol: &str,
+        chain: Option<&str>,
+        pid: Option<u32>,
+        error: impl fmt::Display,
+    ) {
+        let failure = Failure {
+            protocol: protocol.to_string(),
+            chain: chain.map(|c| c.to_string()),
+            pid,
+            error: error.to_string(),
+        };
+        log::warn!("failed: {}", failure);
+        self.failures.lock().unwrap().push(failure);
+    }
+
+    pub fn failures(&self) -> Vec<Failure> {
+        self.failures.lock().unwrap().clone()
+    }
+
+    pub fn print(&self) {
+        let failures = self.failures();
+        println!("------------------------------\nrun report");
+        if failures.is_empty() {
+            println!("all jobs succeeded");
+            return;
+        }
+        println!("{} failures", failures.len());
+        for failure in failures.iter() {
+            println!("- {}", failure);
+        }
+    }
+}
@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+
+use mongodb::bson;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::models;
+use crate::store;
+
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// Score of a risk factor there's no history to judge yet.
+const NEUTRAL_SCORE: f64 = 0.5;
+
+/// A farm's risk factor scores, each from 0 for the riskiest farms to 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct RiskScores {
+    /// How long the farm has been watched, up to `mature_days`.
+    pub age: f64,
+    /// 1 while the tvl holds or grows over `trend_days`, less the more of it left.
+    pub tvl_trend: f64,
+    /// Daily price volatility of the most volatile reward token.
+    pub reward_volatility: f64,
+    /// Deviation from their peg of the farm's stables, 1 without stables.
+    pub peg: f64,
+    /// Impermanent loss exposure of the farm's type.
+    pub impermanent_loss: f64,
+    /// Audit status of the farm's protocol.
+    pub audit: f64,
+    /// The farm's implementation (Solidity, Vyper, Ink or Pallet).
+    pub implementation: f64,
+}
+
+impl RiskScores {
+    pub fn weighted(&self, weights: &config::ScoreWeights) -> f64 {
+        self.age * weights.age
+            + self.tvl_trend * weights.tvl_trend
+            + self.reward_volatility * weights.reward_volatility
+            + self.peg * weights.peg
+            + self.impermanent_loss * weights.impermanent_loss
+            + self.audit * weights.audit
+            + self.implementation * weights.implementation
+    }
+
+    pub fn write(&self, farm: &mut models::Farm) {
+        farm.age_score = Some(self.age);
+        farm.tvl_trend_score = Some(self.tvl_trend);
+        farm.reward_volatility_score = Some(self.reward_volatility);
+        farm.peg_score = Some(self.peg);
+        farm.impermanent_loss_score = Some(self.impermanent_loss);
+        farm.audit_score = Some(self.audit);
+        farm.implementation_score = Some(self.implementation);
+    }
+}
+
+type FarmKey = (i32, String, String, String, String);
+type AssetKey = (String, String, String);
+
+fn farm_key(farm: &models::Farm) -> FarmKey {
+    (
+        farm.id,
+        farm.chef.clone(),
+        farm.chain.clone(),
+        farm.protocol.clone(),
+        farm.asset.address.clone(),
+    )
+}
+
+/// The snapshots and assets the risk factors are judged from, read once per scoring run.
+pub struct History {
+    now: i64,
+    farms: HashMap<FarmKey, Vec<models::FarmSnapshot>>,
+    /// Closing price of every day, oldest first.
+    daily_prices: HashMap<AssetKey, Vec<f64>>,
+    assets: Vec<models::Asset>,
+}
+
+impl History {
+    pub async fn load(
+        store: &dyn store::Store,
+        risk: &config::RiskParams,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let now = bson::DateTime::now().timestamp_millis();
+        let farm_days = risk.mature_days.max(risk.trend_days) as i64;
+
+        let mut farms: HashMap<FarmKey, Vec<models::FarmSnapshot>> = HashMap::new();
+        let since = bson::DateTime::from_millis(now - farm_days * DAY_MILLIS);
+        for s in store.farm_snapshots(since).await? {
+            let key = (
+                s.id,
+                s.chef.clone(),
+                s.chain.clone(),
+                s.protocol.clone(),
+                s.asset_address.clone(),
+            );
+            farms.entry(key).or_default().push(s);
+        }
+
+        // snapshots come oldest first, so the last one of a day is its closing price
+        let mut closes: HashMap<AssetKey, Vec<(i64, f64)>> = HashMap::new();
+        let since = bson::DateTime::from_millis(now - risk.volatility_days as i64 * DAY_MILLIS);
+        for s in store.asset_snapshots(since).await? {
+            let day = s.timestamp.timestamp_millis() / DAY_MILLIS;
+            let prices = closes.entry((s.address, s.chain, s.protocol)).or_default();
+            match prices.last_mut() {
+                Some(last) if last.0 == day => last.1 = s.price,
+                _ => prices.push((day, s.price)),
+            }
+        }
+        let daily_prices = closes
+            .into_iter()
+            .map(|(k, prices)| (k, prices.into_iter().map(|p| p.1).collect()))
+            .collect();
+
+        log::debug!("risk history of {} farms", farms.len());
+
+        Ok(History {
+            now,
+            farms,
+            daily_prices,
+            assets: store.assets().await?,
+        })
+    }
+
+    pub fn scores(&self, farm: &models::Farm, scoring: &config::Scoring) -> RiskScores {
+        let snapshots = self
+            .farms
+            .get(&farm_key(farm))
+            .map_or(&[][..], |s| s.as_slice());
+
+        RiskScores {
+            age: self.age_score(snapshots, &scoring.risk),
+            tvl_trend: self.tvl_trend_score(farm, snapshots, &scoring.risk),
+            reward_volatility: self.reward_volatility_score(farm, &scoring.risk),
+            peg: self.peg_score(farm, &scoring.risk),
+            impermanent_loss: scoring
+                .impermanent_loss_scores
+                .get(&farm.farm_type)
+                .copied()
+                .unwrap_or(NEUTRAL_SCORE),
+            audit: scoring
+                .audit_scores
+                .score(scoring.audit_status(&farm.protocol)),
+            implementation: scoring
+                .implementation_scores
+                .get(&farm.farm_impl)
+                .copied()
+                .unwrap_or(NEUTRAL_SCORE),
+        }
+    }
+
+    // a farm is as old as its oldest snapshot, so a new farm scores close to 0
+    fn age_score(&self, snapshots: &[models::FarmSnapshot], risk: &config::RiskParams) -> f64 {
+        match snapshots.first() {
+            Some(first) => {
+                let days =
+                    (self.now - first.timestamp.timestamp_millis()) as f64 / DAY_MILLIS as f64;
+                (days / risk.mature_days as f64).min(1.0)
+            }
+            None => NEUTRAL_SCORE,
+        }
+    }
+
+    // the tvl now against the oldest snapshot within `trend_days`
+    fn tvl_trend_score(
+        &self,
+        farm: &models::Farm,
+        snapshots: &[models::FarmSnapshot],
+        risk: &config::RiskParams,
+    ) -> f64 {
+        let since = self.now - risk.trend_days as i64 * DAY_MILLIS;
+        match snapshots
+            .iter()
+            .find(|s| s.timestamp.timestamp_millis() >= since)
+        {
+            Some(past) if past.tvl > 0.0 => (farm.tvl / past.tvl).min(1.0),
+            _ => NEUTRAL_SCORE,
+        }
+    }
+
+    fn reward_volatility_score(&self, farm: &models::Farm, risk: &config::RiskParams) -> f64 {
+        let rewards: Vec<&models::Reward> =
+            farm.rewards.iter().filter(|r| r.value_usd > 0.0).collect();
+        if rewards.is_empty() {
+            return 1.0;
+        }
+
+        // rewards are listed by symbol, the farm's own protocol's asset being preferred
+        let volatilities: Vec<f64> = rewards
+            .iter()
+            .filter_map(|r| {
+                let mut assets = self
+                    .assets
+                    .iter()
+                    .filter(|a| a.chain == farm.chain && a.symbol == r.asset && !a.is_lp);
+                let asset = assets
+                    .clone()
+                    .find(|a| a.protocol == farm.protocol)
+                    .or_else(|| assets.next())?;
+                let key = (
+                    asset.address.clone(),
+                    asset.chain.clone(),
+                    asset.protocol.clone(),
+                );
+                volatility(self.daily_prices.get(&key)?)
+            })
+            .collect();
+        match volatilities.into_iter().reduce(f64::max) {
+            Some(v) => (1.0 - v / risk.max_volatility).max(0.0),
+            None => NEUTRAL_SCORE,
+        }
+    }
+
+    fn peg_score(&self, farm: &models::Farm, risk: &config::RiskParams) -> f64 {
+        let deviation = farm
+            .asset
+            .underlying_assets
+            .iter()
+            .filter_map(|u| {
+                self.assets
+                    .iter()
+                    .filter(|a| a.chain == farm.chain && a.address == u.address)
+                    .find_map(|a| a.peg)
+            })
+            .map(|peg| {
+                if peg.depegged {
+                    f64::MAX
+                } else {
+                    peg.deviation
+                }
+            })
+            .reduce(f64::max);
+        match deviation {
+            Some(d) => (1.0 - d / risk.max_peg_deviation).max(0.0),
+            None => 1.0,
+        }
+    }
+}
+
+/// Standard deviation of the daily log returns, unset with fewer than two returns.
+fn volatility(daily_prices: &[f64]) -> Option<f64> {
+    let returns: Vec<f64> = daily_prices
+        .windows(2)
+        .filter(|w| w[0] > 0.0 && w[1] > 0.0)
+        .map(|w| (w[1] / w[0]).ln())
+        .collect();
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+    let variance =
+        returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+    Some(variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: i64 = 1_000 * DAY_MILLIS;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    fn history(daily_prices: Vec<f64>, assets: Vec<models::Asset>) -> History {
+        let key = (
+            "0xr".to_string(),
+            "moonbeam".to_string(),
+            "beamswap".to_string(),
+        );
+        History {
+            now: NOW,
+            farms: HashMap::new(),
+            daily_prices: HashMap::from([(key, daily_prices)]),
+            assets,
+        }
+    }
+
+    fn farm(tvl: f64) -> models::Farm {
+        models::Farm {
+            chain: "moonbeam".to_string(),
+            protocol: "beamswap".to_string(),
+            tvl,
+            ..Default::default()
+        }
+    }
+
+    fn snapshot(days_ago: i64, tvl: f64) -> models::FarmSnapshot {
+        farm(tvl).snapshot(bson::DateTime::from_millis(NOW - days_ago * DAY_MILLIS))
+    }
+
+    fn asset(address: &str, symbol: &str, peg: Option<models::Peg>) -> models::Asset {
+        models::Asset {
+            address: address.to_string(),
+            chain: "moonbeam".to_string(),
+            protocol: "beamswap".to_string(),
+            symbol: symbol.to_string(),
+            peg,
+            ..Default::default()
+        }
+    }
+
+    fn peg(deviation: f64, depegged: bool) -> Option<models::Peg> {
+        Some(models::Peg {
+            peg: 1.0,
+            market_price: 1.0 + deviation,
+            deviation,
+            depegged,
+        })
+    }
+
+    #[test]
+    fn volatility_of_daily_prices() {
+        for (prices, expected) in [
+            (vec![], None),
+            (vec![1.0, 2.0], None),
+            (vec![1.0, 0.0, 1.0, 1.0], None),
+            (vec![1.0, 1.0, 1.0], Some(0.0)),
+            (vec![1.0, 2.0, 1.0], Some(2.0_f64.ln() * 2.0_f64.sqrt())),
+        ] {
+            match (volatility(&prices), expected) {
+                (Some(v), Some(expected)) => assert_close(v, expected),
+                (v, expected) => assert_eq!(v, expected, "{:?}", prices),
+            }
+        }
+    }
+
+    #[test]
+    fn age_score() {
+        let history = history(vec![], vec![]);
+        let risk = config::RiskParams::default();
+        for (snapshots, expected) in [
+            (vec![], NEUTRAL_SCORE),
+            (vec![snapshot(0, 0.0)], 0.0),
+            (vec![snapshot(45, 0.0), snapshot(1, 0.0)], 0.5),
+            (vec![snapshot(180, 0.0)], 1.0),
+        ] {
+            assert_close(history.age_score(&snapshots, &risk), expected);
+        }
+    }
+
+    #[test]
+    fn tvl_trend_score() {
+        let history = history(vec![], vec![]);
+        let risk = config::RiskParams::default();
+        let snapshots = [snapshot(10, 500.0), snapshot(5, 2000.0), snapshot(1, 10.0)];
+        for (tvl, snapshots, expected) in [
+            (1000.0, &snapshots[..], 0.5),
+            (3000.0, &snapshots[..], 1.0),
+            (1000.0, &snapshots[..1], NEUTRAL_SCORE),
+            (1000.0, &[snapshot(5, 0.0)][..], NEUTRAL_SCORE),
+        ] {
+            assert_close(
+                history.tvl_trend_score(&farm(tvl), snapshots, &risk),
+                expected,
+            );
+        }
+    }
+
+    #[test]
+    fn reward_volatility_score() {
+        let prices = vec![1.0, 1.01, 1.0, 1.02];
+        let history = history(prices.clone(), vec![asset("0xr", "GLINT", None)]);
+        let risk = config::RiskParams::default();
+        let reward = |asset: &str, value_usd: f64| models::Reward {
+            amount: 1.0,
+            asset: asset.to_string(),
+            value_usd,
+            freq: "Daily".to_string(),
+        };
+        for (rewards, expected) in [
+            (vec![], 1.0),
+            (vec![reward("GLINT", 0.0)], 1.0),
+            (
+                vec![reward("GLINT", 10.0)],
+                1.0 - volatility(&prices).unwrap() / risk.max_volatility,
+            ),
+            (vec![reward("WGLMR", 10.0)], NEUTRAL_SCORE),
+        ] {
+            let farm = models::Farm {
+                rewards,
+                ..farm(0.0)
+            };
+            assert_close(history.reward_volatility_score(&farm, &risk), expected);
+        }
+    }
+
+    #[test]
+    fn peg_score() {
+        let history = history(
+            vec![],
+            vec![
+                asset("0xusdc", "USDC", peg(0.01, false)),
+                asset("0xusdt", "USDT", peg(0.02, false)),
+                asset("0xbusd", "BUSD", peg(0.2, true)),
+                asset("0xglmr", "WGLMR", None),
+            ],
+        );
+        let risk = config::RiskParams::default();
+        for (underlying, expected) in [
+            (vec!["0xglmr"], 1.0),
+            (vec!["0xusdc", "0xglmr"], 0.8),
+            (vec!["0xusdc", "0xusdt"], 0.6),
+            (vec!["0xusdc", "0xbusd"], 0.0),
+        ] {
+            let mut farm = farm(0.0);
+            farm.asset.underlying_assets = underlying
+                .into_iter()
+                .map(|address| models::UnderlyingAsset {
+                    symbol: String::new(),
+                    address: address.to_string(),
+                    decimals: 18,
+                })
+                .collect();
+            assert_close(history.peg_score(&farm, &risk), expected);
+        }
+    }
+}
@@ -1,72 +1,44 @@
-use futures::TryStreamExt;
-use mongodb::{
-    bson::doc,
-    options::{ClientOptions, FindOneAndUpdateOptions, FindOptions},
-    Client as MongoClient,
-};
 use serde::{Deserialize, Serialize};
 
+use crate::config;
 use crate::models;
-
-pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::Error>> {
-    let mut client_options = ClientOptions::parse(mongo_uri).await?;
-    client_options.app_name = Some("Bay Watcher".to_string());
-    let client = MongoClient::with_options(client_options)?;
-    let db_name = dotenv::var("DB_NAME").unwrap();
-    let db = client.database(&db_name);
-
-    let farms_collection = db.collection::<models::Farm>("farms");
-
-    let null_score_filter = doc! {
-        "totalScore": {"$exists":false}
-    };
-
-    let mut null_score_farms_cursor = farms_collection.find(null_score_filter, None).await?;
-
-    while let Some(farm) = null_score_farms_cursor.try_next().await? {
-        println!(
-            "nullScoreFarm {:?} {:?} {:?} {:?} {:?}",
-            farm.id,
-            farm.chef.clone(),
-            farm.chain.clone(),
-            farm.protocol.clone(),
-            farm.asset.address.clone()
-        );
-        let filter = doc! { "id": farm.id, "chef": farm.chef, "chain": farm.chain, "protocol": farm.protocol, "asset.address": farm.asset.address };
-        let update = doc! { "$set": { "totalScore": 0, "tvlScore": 0, "baseAPRScore": 0, "rewardAPRScore": 0, "rewardsScore": 0 } };
-
-        farms_collection
-            .update_one(filter, update, None)
-            .await
-            .unwrap();
-    }
-
-    // let f = doc! {
-    //     "allocPoint" : {
-    //         "$ne": 0,
-    //     }
-    // };
-
-    let f = doc! {
-        "$and": [
-            {
-                "$or": [
-                    { "protocol": "sushiswap" },
-                    { "protocol": { "$nin": ["sushiswap"] }, "allocPoint": { "$exists": true, "$gt": 0 } }
-                ]
-            },
-            {
-                "$or": [
-                    { "id": { "$nin": [31, 34, 10, 29, 30, 28] } },
-                    { "chef": { "$nin": ["0xF3a5454496E26ac57da879bf3285Fa85DEBF0388"] } },
-                ]
+use crate::risk;
+use crate::store;
+
+pub async fn safety_score(
+    store: &dyn store::Store,
+    scoring: &config::Scoring,
+) -> Result<(), Box<dyn std::error::Error>> {
+    log::info!("scoring version {}", scoring.version);
+    let all_farms = store.farms().await?;
+
+    // excluded farms keep the reason instead of scores, so they're told apart from farms
+    // that were never scored
+    let mut eligible: Vec<models::Farm> = vec![];
+    for mut farm in all_farms {
+        let exclusion = scoring
+            .rule(&farm)
+            .filter(|r| r.action == config::RuleAction::Exclude);
+        match exclusion {
+            Some(rule) => {
+                log::debug!(
+                    "excluded farm {:?} {:?} {:?} {:?}: {}",
+                    farm.id,
+                    farm.chef,
+                    farm.protocol,
+                    farm.asset.symbol,
+                    rule.reason
+                );
+                farm.clear_scores();
+                farm.score_version = Some(scoring.version.clone());
+                farm.score_exclusion = Some(rule.reason.clone());
+                store.update_farm_scores(&farm).await?;
             }
-        ],
-        "asset.symbol": { "$nin": ["xStella", "veSOLAR", "veFLARE", "veFLARE-veSOLAR LP"] },
-    };
-
-    let options = FindOptions::builder().build();
+            None => eligible.push(farm),
+        }
+    }
 
+    let history = risk::History::load(store, &scoring.risk).await?;
     let mut farms = vec![];
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -82,6 +54,8 @@ pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::E
         base_apr: f64,
         reward_apr: f64,
         rewards_usd: f64,
+        terms: models::FarmTerms,
+        risk: risk::RiskScores,
     }
 
     #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -102,21 +76,18 @@ pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::E
         base_apr_score: f64,
         reward_apr_score: f64,
         rewards_score: f64,
+        risk: risk::RiskScores,
     }
 
-    struct Weightage {
-        tvl: f64,
-        base_apr: f64,
-        reward_apr: f64,
-        rewards_usd: f64,
-    }
-
-    let mut farms_cursor = farms_collection.find(f, Some(options)).await?;
-    while let Some(farm) = farms_cursor.try_next().await? {
-        println!(
+    for farm in eligible.iter().cloned() {
+        log::debug!(
             "farm: {:?} {:?} {:?}",
-            farm.asset.address, farm.protocol, farm.chain
+            farm.asset.address,
+            farm.protocol,
+            farm.chain
         );
+        let rewards_usd = farm.daily_rewards_usd();
+        let risk = history.scores(&farm, scoring);
         farms.push(Farm {
             id: farm.id,
             asset_addr: farm.asset.address,
@@ -128,24 +99,13 @@ pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::E
             tvl: farm.tvl,
             base_apr: farm.apr.base,
             reward_apr: farm.apr.reward,
-            rewards_usd: farm
-                .rewards
-                .iter()
-                .map(|x| {
-                    if x.freq == "Weekly" {
-                        return x.value_usd / 7.0;
-                    } else if x.freq == "Monthly" {
-                        return x.value_usd / 30.0;
-                    } else if x.freq == "Annually" {
-                        return x.value_usd / 365.0;
-                    }
-                    return x.value_usd;
-                })
-                .sum(),
+            rewards_usd,
+            terms: farm.terms,
+            risk,
         })
     }
 
-    println!("fl: {:?}", farms.len());
+    log::debug!("fl: {:?}", farms.len());
 
     fn reward_scores(farms: Vec<Farm>) -> Vec<f64> {
         let mut scores = vec![];
@@ -164,7 +124,7 @@ pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::E
                 scores.push(farm.rewards_usd / max_reward)
             } else {
                 scores.push(0.0);
-                println!("edge case reward_scores");
+                log::debug!("edge case reward_scores");
             }
         }
         scores
@@ -187,13 +147,13 @@ pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::E
                 scores.push(farm.reward_apr / max_apr)
             } else {
                 scores.push(0.0);
-                println!("edge case reward_apr_scores");
+                log::debug!("edge case reward_apr_scores");
             }
         }
         scores
     }
 
-    fn base_apr_scores(farms: Vec<Farm>) -> Vec<f64> {
+    fn base_apr_scores(farms: Vec<Farm>, scoring: &config::Scoring) -> Vec<f64> {
         let mut scores = vec![];
         let base_aprs: Vec<f64> = farms.iter().map(|x| x.base_apr).collect();
         let mut max_apr = 0.0;
@@ -204,78 +164,60 @@ pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::E
         }
 
         for farm in farms {
-            if farm.farm_type == "StableAmm" {
-                scores.push(0.6)
-            } else if farm.farm_type == "SingleStaking" {
-                scores.push(0.3)
+            if let Some(score) = scoring.farm_type_base_apr_scores.get(&farm.farm_type) {
+                scores.push(*score)
+            } else if farm.base_apr == max_apr && max_apr != 0.0 {
+                scores.push(1.0)
+            } else if max_apr != 0.0 {
+                scores.push(farm.base_apr / max_apr)
             } else {
-                if farm.base_apr == max_apr && max_apr != 0.0 {
-                    scores.push(1.0)
-                } else if max_apr != 0.0 {
-                    scores.push(farm.base_apr / max_apr)
-                } else {
-                    scores.push(0.0);
-                    println!("edge case base_apr_scores");
-                }
+                scores.push(0.0);
+                log::debug!("edge case base_apr_scores");
             }
         }
         scores
     }
 
-    fn tvl_scores(farms: Vec<Farm>) -> Vec<f64> {
-        let mut scores = vec![];
-        for farm in farms {
-            let mut score = 0.0;
-            // > $10M |  1.00
-            if farm.tvl >= 10000000.0 {
-                score = 1.0
-            }
-            // $1M - $10M |  0.85
-            else if farm.tvl >= 1000000.0 && farm.tvl < 10000000.0 {
-                score = 0.85
-            }
-            // $100K - $1M |  0.75
-            else if farm.tvl >= 100000.0 && farm.tvl < 1000000.0 {
-                score = 0.75
-            }
-            // $10K - $100K |  0.6
-            else if farm.tvl >= 10000.0 && farm.tvl < 100000.0 {
-                score = 0.6
-            }
-            // $1K - $10K |  0.5
-            else if farm.tvl >= 1000.0 && farm.tvl < 10000.0 {
-                score = 0.5
-            }
-            // // < $1K |  0.00
-            // else {
-            //     score = 0.0
-            // }
-            scores.push(score)
-        }
-        scores
+    fn tvl_scores(farms: Vec<Farm>, scoring: &config::Scoring) -> Vec<f64> {
+        // the buckets are sorted from the highest tvl down
+        farms
+            .iter()
+            .map(|farm| {
+                scoring
+                    .tvl_buckets
+                    .iter()
+                    .find(|bucket| farm.tvl >= bucket.min)
+                    .map_or(0.0, |bucket| bucket.score)
+            })
+            .collect()
+    }
+
+    // a 4% deposit fee costs a farm 0.04, and rewards or deposits held for a month or more
+    // another `lockup_penalty`
+    fn terms_penalty(terms: &models::FarmTerms, scoring: &config::Scoring) -> f64 {
+        let held_months =
+            terms.harvest_interval.max(terms.lockup) as f64 / (30.0 * 24.0 * 60.0 * 60.0);
+        terms.deposit_fee + terms.withdrawal_fee + held_months.min(1.0) * scoring.lockup_penalty
     }
 
-    let weightage = Weightage {
-        tvl: 0.45,
-        base_apr: 0.2,
-        reward_apr: 0.15,
-        rewards_usd: 0.2,
-    };
+    let weights = scoring.weights;
 
-    let tvl = tvl_scores(farms.clone()); // 45%
-    let base_apr = base_apr_scores(farms.clone()); // 20%
-    let reward_apr = reward_apr_scores(farms.clone()); // 15%
-    let rewards = reward_scores(farms.clone()); // 20%
+    let tvl = tvl_scores(farms.clone(), scoring);
+    let base_apr = base_apr_scores(farms.clone(), scoring);
+    let reward_apr = reward_apr_scores(farms.clone());
+    let rewards = reward_scores(farms.clone());
 
     let mut safety_scores = vec![];
     let mut min_score = 100.0;
     let mut max_score = -100.0;
     for i in 0..farms.len() {
         let farm = farms[i].clone();
-        let total_score = tvl[i] * weightage.tvl
-            + base_apr[i] * weightage.base_apr
-            + reward_apr[i] * weightage.reward_apr
-            + rewards[i] * weightage.rewards_usd;
+        let total_score = tvl[i] * weights.tvl
+            + base_apr[i] * weights.base_apr
+            + reward_apr[i] * weights.reward_apr
+            + rewards[i] * weights.rewards_usd
+            + farm.risk.weighted(&weights)
+            - terms_penalty(&farm.terms, scoring);
         safety_scores.push(FarmSafetyScore {
             id: farm.id,
             asset_addr: farm.asset_addr,
@@ -293,6 +235,7 @@ pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::E
             base_apr_score: base_apr[i],
             reward_apr_score: reward_apr[i],
             rewards_score: rewards[i],
+            risk: farm.risk,
         });
         if total_score > max_score {
             max_score = total_score;
@@ -302,16 +245,16 @@ pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::E
         }
     }
 
-    println!("safety_scores {:?}", safety_scores.clone());
+    log::debug!("safety_scores {:?}", safety_scores.clone());
 
     for i in 0..safety_scores.len() {
-        println!("min_score {:?} max_score {:?}", min_score, max_score);
+        log::debug!("min_score {:?} max_score {:?}", min_score, max_score);
         safety_scores[i].total_score =
             (safety_scores[i].total_score - min_score) / ((max_score - min_score) * 1.01);
 
         let obj = safety_scores[i].clone();
 
-        println!(
+        log::debug!(
             "idx {:?} {:?} {:?} baseapr {:?} rewardapr {:?} rewards {:?} tvl {:?} ts {:?} fs {:?}",
             i,
             obj.id,
@@ -324,33 +267,16 @@ pub async fn safety_score(mongo_uri: String) -> Result<(), Box<dyn std::error::E
             obj.total_score.clone()
         );
 
-        let ff = doc! {
-            "id": obj.id.clone(),
-            "chef": obj.chef.clone(),
-            "chain": obj.chain.clone(),
-            "protocol": obj.protocol.clone(),
-            "asset.address": obj.asset_addr.clone(),
-        };
-        let fu = doc! {
-            "$set" : {
-                "id": obj.id.clone(),
-                "chef": obj.chef.clone(),
-                "chain": obj.chain.clone(),
-                "protocol": obj.protocol.clone(),
-                "asset.address": obj.asset_addr.clone(),
-                "totalScore": obj.total_score.clone(),
-                "tvlScore": obj.tvl_score.clone(),
-                "baseAPRScore": obj.base_apr_score.clone(),
-                "rewardAPRScore": obj.reward_apr_score.clone(),
-                "rewardsScore": obj.rewards_score.clone(),
-            }
-        };
-        let options = FindOneAndUpdateOptions::builder()
-            .upsert(Some(true))
-            .build();
-        farms_collection
-            .find_one_and_update(ff, fu, Some(options))
-            .await?;
+        let mut farm = eligible[i].clone();
+        farm.total_score = Some(obj.total_score);
+        farm.tvl_score = Some(obj.tvl_score);
+        farm.base_apr_score = Some(obj.base_apr_score);
+        farm.reward_apr_score = Some(obj.reward_apr_score);
+        farm.rewards_score = Some(obj.rewards_score);
+        obj.risk.write(&mut farm);
+        farm.score_version = Some(scoring.version.clone());
+        farm.score_exclusion = None;
+        store.update_farm_scores(&farm).await?;
     }
 
     Ok(())
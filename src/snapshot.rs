@@ -0,0 +1,55 @@
+use mongodb::bson;
+
+use crate::config;
+use crate::store;
+
+const DAY_MILLIS: i64 = 24 * 60 * 60 * 1000;
+
+/// Appends a snapshot of every farm and asset, then prunes the ones past their retention.
+pub async fn snapshot_jobs(
+    store: &dyn store::Store,
+    config: &config::Snapshots,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let now = bson::DateTime::now();
+
+    let farm_snapshots: Vec<_> = store
+        .farms()
+        .await?
+        .iter()
+        .map(|f| f.snapshot(now))
+        .collect();
+    store.insert_farm_snapshots(&farm_snapshots).await?;
+
+    let asset_snapshots: Vec<_> = store
+        .assets()
+        .await?
+        .iter()
+        .map(|a| a.snapshot(now))
+        .collect();
+    store.insert_asset_snapshots(&asset_snapshots).await?;
+
+    log::info!(
+        "snapshotted {} farms and {} assets",
+        farm_snapshots.len(),
+        asset_snapshots.len()
+    );
+
+    let pruned_farms = store
+        .prune_farm_snapshots(retention_cutoff(now, config.farm_retention_days))
+        .await?;
+    let pruned_assets = store
+        .prune_asset_snapshots(retention_cutoff(now, config.asset_retention_days))
+        .await?;
+
+    log::info!(
+        "pruned {} farm snapshots and {} asset snapshots",
+        pruned_farms,
+        pruned_assets
+    );
+
+    Ok(())
+}
+
+fn retention_cutoff(now: bson::DateTime, days: u32) -> bson::DateTime {
+    bson::DateTime::from_millis(now.timestamp_millis() - days as i64 * DAY_MILLIS)
+}
@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use futures::future::LocalBoxFuture;
+use mongodb::bson;
+
+use crate::models;
+use crate::store::{
+    self, memory::MemoryStore, mongo::MongoStore, AssetStore, FarmStore, SnapshotStore,
+    StoreResult, TokenStore,
+};
+
+/// Reads from mongo and writes to memory, so a run can be inspected without
+/// touching the database. Reads see the run's own writes first. Snapshots are
+/// only appended to and pruned from memory.
+pub struct DryRunStore {
+    pub mongo: MongoStore,
+    pub memory: Arc<MemoryStore>,
+}
+
+impl AssetStore for DryRunStore {
+    fn assets<'a>(&'a self) -> LocalBoxFuture<'a, StoreResult<Vec<models::Asset>>> {
+        Box::pin(async move {
+            let written = self.memory.assets().await?;
+            let key = |a: &models::Asset| store::asset_filter(&a.address, &a.chain, &a.protocol);
+            let mut assets: Vec<models::Asset> = self
+                .mongo
+                .assets()
+                .await?
+                .into_iter()
+                .filter(|a| !written.iter().any(|w| key(w) == key(a)))
+                .collect();
+            assets.extend(written);
+            Ok(assets)
+        })
+    }
+
+    fn find_asset<'a>(
+        &'a self,
+        address: &'a str,
+        chain: &'a str,
+        protocol: &'a str,
+    ) -> LocalBoxFuture<'a, StoreResult<Option<models::Asset>>> {
+        Box::pin(async move {
+            match self.memory.find_asset(address, chain, protocol).await? {
+                Some(asset) => Ok(Some(asset)),
+                None => self.mongo.find_asset(address, chain, protocol).await,
+            }
+        })
+    }
+
+    fn upsert_asset<'a>(&'a self, asset: &'a models::Asset) -> LocalBoxFuture<'a, StoreResult<()>> {
+        self.memory.upsert_asset(asset)
+    }
+}
+
+impl FarmStore for DryRunStore {
+    fn farms<'a>(&'a self) -> LocalBoxFuture<'a, StoreResult<Vec<models::Farm>>> {
+        Box::pin(async move {
+            let written = self.memory.farms().await?;
+            let mut farms: Vec<models::Farm> = self
+                .mongo
+                .farms()
+                .await?
+                .into_iter()
+                .filter(|f| {
+                    !written
+                        .iter()
+                        .any(|w| store::farm_filter(w) == store::farm_filter(f))
+                })
+                .collect();
+            farms.extend(written);
+            Ok(farms)
+        })
+    }
+
+    fn upsert_farm<'a>(&'a self, farm: &'a models::Farm) -> LocalBoxFuture<'a, StoreResult<()>> {
+        self.memory.upsert_farm(farm)
+    }
+
+    // the farm may only be in mongo so far, so it's written whole first
+    fn update_farm_scores<'a>(
+        &'a self,
+        farm: &'a models::Farm,
+    ) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            self.memory.upsert_farm(farm).await?;
+            self.memory.update_farm_scores(farm).await
+        })
+    }
+}
+
+impl SnapshotStore for DryRunStore {
+    fn insert_farm_snapshots<'a>(
+        &'a self,
+        snapshots: &'a [models::FarmSnapshot],
+    ) -> LocalBoxFuture<'a, StoreResult<()>> {
+        self.memory.insert_farm_snapshots(snapshots)
+    }
+
+    fn insert_asset_snapshots<'a>(
+        &'a self,
+        snapshots: &'a [models::AssetSnapshot],
+    ) -> LocalBoxFuture<'a, StoreResult<()>> {
+        self.memory.insert_asset_snapshots(snapshots)
+    }
+
+    // the database's history, followed by any snapshot this run took
+    fn farm_snapshots<'a>(
+        &'a self,
+        since: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<Vec<models::FarmSnapshot>>> {
+        Box::pin(async move {
+            let mut snapshots = self.mongo.farm_snapshots(since).await?;
+            snapshots.extend(self.memory.farm_snapshots(since).await?);
+            Ok(snapshots)
+        })
+    }
+
+    fn asset_snapshots<'a>(
+        &'a self,
+        since: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<Vec<models::AssetSnapshot>>> {
+        Box::pin(async move {
+            let mut snapshots = self.mongo.asset_snapshots(since).await?;
+            snapshots.extend(self.memory.asset_snapshots(since).await?);
+            Ok(snapshots)
+        })
+    }
+
+    fn prune_farm_snapshots<'a>(
+        &'a self,
+        before: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<u64>> {
+        self.memory.prune_farm_snapshots(before)
+    }
+
+    fn prune_asset_snapshots<'a>(
+        &'a self,
+        before: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<u64>> {
+        self.memory.prune_asset_snapshots(before)
+    }
+}
+
+impl TokenStore for DryRunStore {
+    fn find_token<'a>(
+        &'a self,
+        address: &'a str,
+        chain: &'a str,
+    ) -> LocalBoxFuture<'a, StoreResult<Option<models::Token>>> {
+        Box::pin(async move {
+            match self.memory.find_token(address, chain).await? {
+                Some(token) => Ok(Some(token)),
+                None => self.mongo.find_token(address, chain).await,
+            }
+        })
+    }
+
+    fn upsert_token<'a>(&'a self, token: &'a models::Token) -> LocalBoxFuture<'a, StoreResult<()>> {
+        self.memory.upsert_token(token)
+    }
+}
@@ -0,0 +1,444 @@
+use std::{fs, sync::Mutex};
+
+use futures::future::LocalBoxFuture;
+use mongodb::bson::{self, doc, Bson, Document};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use crate::models;
+use crate::store::{self, AssetStore, FarmStore, SnapshotStore, StoreResult, TokenStore};
+
+/// Assets and farms kept in process, as the documents mongo would hold, their snapshots
+/// and resolved tokens.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    assets: Mutex<Vec<Document>>,
+    farms: Mutex<Vec<Document>>,
+    asset_snapshots: Mutex<Vec<models::AssetSnapshot>>,
+    farm_snapshots: Mutex<Vec<models::FarmSnapshot>>,
+    tokens: Mutex<Vec<models::Token>>,
+}
+
+impl MemoryStore {
+    pub fn asset_documents(&self) -> Vec<Document> {
+        self.assets.lock().unwrap().clone()
+    }
+
+    pub fn farm_documents(&self) -> Vec<Document> {
+        self.farms.lock().unwrap().clone()
+    }
+
+    /// The stored assets, farms, snapshots and tokens as json, checked against `models::Asset`/`models::Farm`.
+    /// Documents that don't deserialize into the model are kept as-is with the error.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "assets": self
+                .asset_documents()
+                .into_iter()
+                .map(typed::<models::Asset>)
+                .collect::<Vec<Value>>(),
+            "farms": self
+                .farm_documents()
+                .into_iter()
+                .map(typed::<models::Farm>)
+                .collect::<Vec<Value>>(),
+            "assetSnapshots": *self.asset_snapshots.lock().unwrap(),
+            "farmSnapshots": *self.farm_snapshots.lock().unwrap(),
+            "tokens": *self.tokens.lock().unwrap(),
+        })
+    }
+
+    /// Prints the stored documents, or writes them to `path` when given.
+    pub fn dump(&self, path: Option<&str>) -> StoreResult<()> {
+        let out = serde_json::to_string_pretty(&self.to_json())?;
+        match path {
+            Some(path) => {
+                fs::write(path, out)?;
+                println!("dry run documents written to {}", path);
+            }
+            None => println!("dry run documents\n{}", out),
+        }
+        Ok(())
+    }
+}
+
+/// Applies an update the way an upsert would: the first document whose fields
+/// match the (equality) filter gets the `$set` fields and loses the `$unset` ones,
+/// otherwise a new document is built from the filter and the `$set` fields.
+fn apply(docs: &Mutex<Vec<Document>>, filter: Document, update: Document) {
+    let unset = update.get_document("$unset").cloned().unwrap_or_default();
+    let set = match update.get_document("$set") {
+        Ok(set) => set.clone(),
+        Err(_) => update,
+    };
+
+    let mut docs = docs.lock().unwrap();
+    match docs
+        .iter_mut()
+        .find(|d| filter.iter().all(|(k, v)| get_path(d, k) == Some(v)))
+    {
+        Some(doc) => {
+            for (k, v) in set {
+                set_path(doc, &k, v);
+            }
+            for k in unset.keys() {
+                doc.remove(k);
+            }
+        }
+        None => {
+            let mut doc = Document::new();
+            for (k, v) in filter.into_iter().chain(set) {
+                set_path(&mut doc, &k, v);
+            }
+            docs.push(doc);
+        }
+    }
+}
+
+fn find(docs: &Mutex<Vec<Document>>, filter: &Document) -> Option<Document> {
+    docs.lock()
+        .unwrap()
+        .iter()
+        .find(|d| filter.iter().all(|(k, v)| get_path(d, k) == Some(v)))
+        .cloned()
+}
+
+fn typed<T: DeserializeOwned + Serialize>(doc: Document) -> Value {
+    match bson::from_document::<T>(doc.clone()) {
+        Ok(model) => serde_json::to_value(model).unwrap_or(Value::Null),
+        Err(e) => json!({
+            "error": e.to_string(),
+            "document": Bson::Document(doc).into_relaxed_extjson(),
+        }),
+    }
+}
+
+/// Looks up a dotted path such as `asset.address`.
+fn get_path<'a>(doc: &'a Document, path: &str) -> Option<&'a Bson> {
+    match path.split_once('.') {
+        Some((head, rest)) => get_path(doc.get_document(head).ok()?, rest),
+        None => doc.get(path),
+    }
+}
+
+/// Sets a dotted path such as `apr.reward`, creating the parent documents.
+fn set_path(doc: &mut Document, path: &str, value: Bson) {
+    match path.split_once('.') {
+        Some((head, rest)) => {
+            if doc.get_document(head).is_err() {
+                doc.insert(head, Document::new());
+            }
+            if let Ok(parent) = doc.get_document_mut(head) {
+                set_path(parent, rest, value);
+            }
+        }
+        None => {
+            doc.insert(path, value);
+        }
+    }
+}
+
+impl AssetStore for MemoryStore {
+    fn assets<'a>(&'a self) -> LocalBoxFuture<'a, StoreResult<Vec<models::Asset>>> {
+        Box::pin(async move {
+            let mut assets = vec![];
+            for doc in self.asset_documents() {
+                assets.push(bson::from_document(doc)?);
+            }
+            Ok(assets)
+        })
+    }
+
+    fn find_asset<'a>(
+        &'a self,
+        address: &'a str,
+        chain: &'a str,
+        protocol: &'a str,
+    ) -> LocalBoxFuture<'a, StoreResult<Option<models::Asset>>> {
+        Box::pin(async move {
+            let filter = store::asset_filter(address, chain, protocol);
+            match find(&self.assets, &filter) {
+                Some(doc) => Ok(Some(bson::from_document(doc)?)),
+                None => Ok(None),
+            }
+        })
+    }
+
+    fn upsert_asset<'a>(&'a self, asset: &'a models::Asset) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            let filter = store::asset_filter(&asset.address, &asset.chain, &asset.protocol);
+            apply(
+                &self.assets,
+                filter,
+                doc! { "$set": bson::to_document(asset)? },
+            );
+            Ok(())
+        })
+    }
+}
+
+impl FarmStore for MemoryStore {
+    fn farms<'a>(&'a self) -> LocalBoxFuture<'a, StoreResult<Vec<models::Farm>>> {
+        Box::pin(async move {
+            let mut farms = vec![];
+            for doc in self.farm_documents() {
+                farms.push(bson::from_document(doc)?);
+            }
+            Ok(farms)
+        })
+    }
+
+    fn upsert_farm<'a>(&'a self, farm: &'a models::Farm) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            apply(
+                &self.farms,
+                store::farm_filter(farm),
+                doc! { "$set": bson::to_document(farm)? },
+            );
+            Ok(())
+        })
+    }
+
+    fn update_farm_scores<'a>(
+        &'a self,
+        farm: &'a models::Farm,
+    ) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            apply(
+                &self.farms,
+                store::farm_filter(farm),
+                store::score_update(farm)?,
+            );
+            Ok(())
+        })
+    }
+}
+
+fn prune<T>(
+    snapshots: &Mutex<Vec<T>>,
+    taken_at: impl Fn(&T) -> bson::DateTime,
+    before: bson::DateTime,
+) -> u64 {
+    let mut snapshots = snapshots.lock().unwrap();
+    let len = snapshots.len();
+    snapshots.retain(|s| taken_at(s) >= before);
+    (len - snapshots.len()) as u64
+}
+
+fn taken_since<T: Clone>(
+    snapshots: &Mutex<Vec<T>>,
+    taken_at: impl Fn(&T) -> bson::DateTime,
+    since: bson::DateTime,
+) -> Vec<T> {
+    let mut taken: Vec<T> = snapshots
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|s| taken_at(s) >= since)
+        .cloned()
+        .collect();
+    taken.sort_by_key(|s| taken_at(s));
+    taken
+}
+
+impl SnapshotStore for MemoryStore {
+    fn insert_farm_snapshots<'a>(
+        &'a self,
+        snapshots: &'a [models::FarmSnapshot],
+    ) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            self.farm_snapshots
+                .lock()
+                .unwrap()
+                .extend_from_slice(snapshots);
+            Ok(())
+        })
+    }
+
+    fn insert_asset_snapshots<'a>(
+        &'a self,
+        snapshots: &'a [models::AssetSnapshot],
+    ) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            self.asset_snapshots
+                .lock()
+                .unwrap()
+                .extend_from_slice(snapshots);
+            Ok(())
+        })
+    }
+
+    fn farm_snapshots<'a>(
+        &'a self,
+        since: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<Vec<models::FarmSnapshot>>> {
+        Box::pin(async move { Ok(taken_since(&self.farm_snapshots, |s| s.timestamp, since)) })
+    }
+
+    fn asset_snapshots<'a>(
+        &'a self,
+        since: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<Vec<models::AssetSnapshot>>> {
+        Box::pin(async move { Ok(taken_since(&self.asset_snapshots, |s| s.timestamp, since)) })
+    }
+
+    fn prune_farm_snapshots<'a>(
+        &'a self,
+        before: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<u64>> {
+        Box::pin(async move { Ok(prune(&self.farm_snapshots, |s| s.timestamp, before)) })
+    }
+
+    fn prune_asset_snapshots<'a>(
+        &'a self,
+        before: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<u64>> {
+        Box::pin(async move { Ok(prune(&self.asset_snapshots, |s| s.timestamp, before)) })
+    }
+}
+
+impl TokenStore for MemoryStore {
+    fn find_token<'a>(
+        &'a self,
+        address: &'a str,
+        chain: &'a str,
+    ) -> LocalBoxFuture<'a, StoreResult<Option<models::Token>>> {
+        Box::pin(async move {
+            Ok(self
+                .tokens
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|t| t.address == address && t.chain == chain)
+                .cloned())
+        })
+    }
+
+    fn upsert_token<'a>(&'a self, token: &'a models::Token) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            let mut tokens = self.tokens.lock().unwrap();
+            tokens.retain(|t| !(t.address == token.address && t.chain == token.chain));
+            tokens.push(token.clone());
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset() -> models::Asset {
+        models::Asset {
+            address: "0xa".to_string(),
+            chain: "moonriver".to_string(),
+            protocol: "solarbeam".to_string(),
+            symbol: "SOLAR".to_string(),
+            price: 1.5,
+            ..Default::default()
+        }
+    }
+
+    fn farm() -> models::Farm {
+        models::Farm {
+            id: 3,
+            chef: "0xc".to_string(),
+            chain: "moonriver".to_string(),
+            protocol: "solarbeam".to_string(),
+            tvl: 1000.0,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn assets_round_trip() {
+        let store = MemoryStore::default();
+        let mut asset = asset();
+        store.upsert_asset(&asset).await.unwrap();
+        asset.price = 2.0;
+        store.upsert_asset(&asset).await.unwrap();
+
+        assert_eq!(store.assets().await.unwrap(), vec![asset.clone()]);
+        let found = store
+            .find_asset(&asset.address, &asset.chain, &asset.protocol)
+            .await
+            .unwrap();
+        assert_eq!(found, Some(asset));
+        let missing = store
+            .find_asset("0xb", "moonriver", "solarbeam")
+            .await
+            .unwrap();
+        assert_eq!(missing, None);
+    }
+
+    #[tokio::test]
+    async fn missing_required_asset_is_an_error() {
+        let store = MemoryStore::default();
+        let asset = asset();
+        let missing =
+            store::required_asset(&store, &asset.address, &asset.chain, &asset.protocol).await;
+        assert_eq!(
+            missing.unwrap_err().to_string(),
+            "missing asset 0xa on moonriver solarbeam"
+        );
+
+        store.upsert_asset(&asset).await.unwrap();
+        let found = store::required_asset(&store, &asset.address, &asset.chain, &asset.protocol)
+            .await
+            .unwrap();
+        assert_eq!(found, asset);
+    }
+
+    #[tokio::test]
+    async fn farms_round_trip() {
+        let store = MemoryStore::default();
+        let mut farm = farm();
+        store.upsert_farm(&farm).await.unwrap();
+        farm.tvl = 2000.0;
+        store.upsert_farm(&farm).await.unwrap();
+        let other = models::Farm {
+            id: 4,
+            ..farm.clone()
+        };
+        store.upsert_farm(&other).await.unwrap();
+
+        assert_eq!(store.farms().await.unwrap(), vec![farm, other]);
+    }
+
+    #[tokio::test]
+    async fn score_update_clears_the_exclusion_once_included() {
+        let store = MemoryStore::default();
+        let mut farm = farm();
+        farm.score_exclusion = Some("inactive".to_string());
+        store.upsert_farm(&farm).await.unwrap();
+
+        farm.score_exclusion = None;
+        farm.total_score = Some(0.8);
+        farm.age_score = Some(1.0);
+        store.update_farm_scores(&farm).await.unwrap();
+
+        let doc = &store.farm_documents()[0];
+        assert!(!doc.contains_key("scoreExclusion"));
+        assert_eq!(doc.get_f64("totalScore").unwrap(), 0.8);
+        assert_eq!(store.farms().await.unwrap(), vec![farm]);
+    }
+
+    #[tokio::test]
+    async fn score_update_clears_the_scores_once_excluded() {
+        let store = MemoryStore::default();
+        let mut farm = farm();
+        farm.total_score = Some(0.8);
+        farm.age_score = Some(1.0);
+        store.upsert_farm(&farm).await.unwrap();
+
+        farm.clear_scores();
+        farm.score_exclusion = Some("inactive".to_string());
+        store.update_farm_scores(&farm).await.unwrap();
+
+        let doc = &store.farm_documents()[0];
+        assert!(!doc.contains_key("totalScore"));
+        assert!(!doc.contains_key("ageScore"));
+        assert_eq!(doc.get_str("scoreExclusion").unwrap(), "inactive");
+        assert_eq!(store.farms().await.unwrap(), vec![farm]);
+    }
+}
@@ -0,0 +1,161 @@
+use futures::future::LocalBoxFuture;
+use mongodb::bson::{self, doc, Document};
+
+use crate::custom;
+use crate::models;
+
+pub(crate) mod dry_run;
+pub(crate) mod memory;
+pub(crate) mod mongo;
+
+pub type StoreResult<T> = Result<T, Box<dyn std::error::Error>>;
+
+pub trait AssetStore {
+    fn assets<'a>(&'a self) -> LocalBoxFuture<'a, StoreResult<Vec<models::Asset>>>;
+
+    fn find_asset<'a>(
+        &'a self,
+        address: &'a str,
+        chain: &'a str,
+        protocol: &'a str,
+    ) -> LocalBoxFuture<'a, StoreResult<Option<models::Asset>>>;
+
+    /// Inserts the asset, or overwrites the one with the same address, chain and protocol.
+    fn upsert_asset<'a>(&'a self, asset: &'a models::Asset) -> LocalBoxFuture<'a, StoreResult<()>>;
+}
+
+pub trait FarmStore {
+    fn farms<'a>(&'a self) -> LocalBoxFuture<'a, StoreResult<Vec<models::Farm>>>;
+
+    /// Inserts the farm, or overwrites the one with the same key (see `farm_filter`).
+    /// Unset scores are left as they are.
+    fn upsert_farm<'a>(&'a self, farm: &'a models::Farm) -> LocalBoxFuture<'a, StoreResult<()>>;
+
+    /// Writes the farm's scores (see `SCORE_FIELDS`) to the stored farm, removing the
+    /// ones that are unset.
+    fn update_farm_scores<'a>(
+        &'a self,
+        farm: &'a models::Farm,
+    ) -> LocalBoxFuture<'a, StoreResult<()>>;
+}
+
+pub trait TokenStore {
+    fn find_token<'a>(
+        &'a self,
+        address: &'a str,
+        chain: &'a str,
+    ) -> LocalBoxFuture<'a, StoreResult<Option<models::Token>>>;
+
+    fn upsert_token<'a>(&'a self, token: &'a models::Token) -> LocalBoxFuture<'a, StoreResult<()>>;
+}
+
+/// Append-only history of farms and assets, pruned to a retention window.
+pub trait SnapshotStore {
+    fn insert_farm_snapshots<'a>(
+        &'a self,
+        snapshots: &'a [models::FarmSnapshot],
+    ) -> LocalBoxFuture<'a, StoreResult<()>>;
+
+    fn insert_asset_snapshots<'a>(
+        &'a self,
+        snapshots: &'a [models::AssetSnapshot],
+    ) -> LocalBoxFuture<'a, StoreResult<()>>;
+
+    /// The farm snapshots taken since `since`, oldest first.
+    fn farm_snapshots<'a>(
+        &'a self,
+        since: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<Vec<models::FarmSnapshot>>>;
+
+    /// The asset snapshots taken since `since`, oldest first.
+    fn asset_snapshots<'a>(
+        &'a self,
+        since: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<Vec<models::AssetSnapshot>>>;
+
+    /// Deletes the farm snapshots taken before `before`, returning how many were deleted.
+    fn prune_farm_snapshots<'a>(
+        &'a self,
+        before: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<u64>>;
+
+    /// Deletes the asset snapshots taken before `before`, returning how many were deleted.
+    fn prune_asset_snapshots<'a>(
+        &'a self,
+        before: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<u64>>;
+}
+
+/// Everything a job reads from and writes to.
+pub trait Store: AssetStore + FarmStore + SnapshotStore + TokenStore {}
+
+impl<T: AssetStore + FarmStore + SnapshotStore + TokenStore> Store for T {}
+
+pub fn asset_filter(address: &str, chain: &str, protocol: &str) -> Document {
+    doc! { "address": address, "chain": chain, "protocol": protocol }
+}
+
+pub fn token_filter(address: &str, chain: &str) -> Document {
+    doc! { "address": address, "chain": chain }
+}
+
+/// Finds an asset others are priced or built from, erroring when it isn't stored so only
+/// the farm or protocol that needs it fails.
+pub async fn required_asset<S: AssetStore + ?Sized>(
+    store: &S,
+    address: &str,
+    chain: &str,
+    protocol: &str,
+) -> StoreResult<models::Asset> {
+    store
+        .find_asset(address, chain, protocol)
+        .await?
+        .ok_or_else(|| format!("missing asset {} on {} {}", address, chain, protocol).into())
+}
+
+/// Farm fields only the scoring job writes.
+pub const SCORE_FIELDS: [&str; 14] = [
+    "totalScore",
+    "tvlScore",
+    "baseAPRScore",
+    "rewardAPRScore",
+    "rewardsScore",
+    "ageScore",
+    "tvlTrendScore",
+    "rewardVolatilityScore",
+    "pegScore",
+    "impermanentLossScore",
+    "auditScore",
+    "implementationScore",
+    "scoreVersion",
+    "scoreExclusion",
+];
+
+/// `$set`s the farm's scores that are set and `$unset`s the others.
+pub fn score_update(farm: &models::Farm) -> StoreResult<Document> {
+    let fields = bson::to_document(farm)?;
+    let mut set = Document::new();
+    let mut unset = Document::new();
+    for field in SCORE_FIELDS {
+        match fields.get(field) {
+            Some(value) => set.insert(field, value.clone()),
+            None => unset.insert(field, ""),
+        };
+    }
+    Ok(doc! { "$set": set, "$unset": unset })
+}
+
+/// A farm is keyed by its pid within a chef, so a pool that goes inactive overwrites
+/// its farm. Pulsar farms all share id 0 and are told apart by their asset.
+pub fn farm_filter(farm: &models::Farm) -> Document {
+    let mut filter = doc! {
+        "id": farm.id,
+        "chef": farm.chef.clone(),
+        "chain": farm.chain.clone(),
+        "protocol": farm.protocol.clone(),
+    };
+    if farm.chef == custom::pulsar::CHEF {
+        filter.insert("asset.address", farm.asset.address.clone());
+    }
+    filter
+}
@@ -0,0 +1,201 @@
+use futures::{future::LocalBoxFuture, TryStreamExt};
+use mongodb::{
+    bson::{self, doc, Document},
+    options::{ClientOptions, FindOneAndUpdateOptions, FindOptions},
+    Client as MongoClient, Collection,
+};
+
+use crate::models;
+use crate::store::{self, AssetStore, FarmStore, SnapshotStore, StoreResult, TokenStore};
+
+/// The `assets`, `farms`, `tokens` and snapshot collections of the database.
+#[derive(Clone)]
+pub struct MongoStore {
+    pub assets_collection: Collection<models::Asset>,
+    pub farms_collection: Collection<models::Farm>,
+    pub asset_snapshots_collection: Collection<models::AssetSnapshot>,
+    pub farm_snapshots_collection: Collection<models::FarmSnapshot>,
+    pub tokens_collection: Collection<models::Token>,
+}
+
+impl MongoStore {
+    pub async fn connect(mongo_uri: &str, db_name: &str) -> StoreResult<Self> {
+        let mut client_options = ClientOptions::parse(mongo_uri).await?;
+        client_options.app_name = Some("Bay Watcher".to_string());
+        let client = MongoClient::with_options(client_options)?;
+        let db = client.database(db_name);
+
+        Ok(MongoStore {
+            assets_collection: db.collection::<models::Asset>("assets"),
+            farms_collection: db.collection::<models::Farm>("farms"),
+            asset_snapshots_collection: db.collection::<models::AssetSnapshot>("asset_snapshots"),
+            farm_snapshots_collection: db.collection::<models::FarmSnapshot>("farm_snapshots"),
+            tokens_collection: db.collection::<models::Token>("tokens"),
+        })
+    }
+}
+
+async fn upsert<T>(
+    collection: &Collection<T>,
+    filter: Document,
+    update: Document,
+) -> StoreResult<()>
+where
+    T: serde::de::DeserializeOwned + Unpin + Send + Sync,
+{
+    let options = FindOneAndUpdateOptions::builder()
+        .upsert(Some(true))
+        .build();
+    collection
+        .find_one_and_update(filter, update, Some(options))
+        .await?;
+    Ok(())
+}
+
+async fn insert<T: serde::Serialize>(collection: &Collection<T>, docs: &[T]) -> StoreResult<()> {
+    if !docs.is_empty() {
+        collection.insert_many(docs, None).await?;
+    }
+    Ok(())
+}
+
+async fn taken_since<T>(collection: &Collection<T>, since: bson::DateTime) -> StoreResult<Vec<T>>
+where
+    T: serde::de::DeserializeOwned + Unpin + Send + Sync,
+{
+    let options = FindOptions::builder().sort(doc! { "timestamp": 1 }).build();
+    let cursor = collection
+        .find(doc! { "timestamp": { "$gte": since } }, Some(options))
+        .await?;
+    Ok(cursor.try_collect().await?)
+}
+
+async fn prune<T>(collection: &Collection<T>, before: bson::DateTime) -> StoreResult<u64> {
+    let result = collection
+        .delete_many(doc! { "timestamp": { "$lt": before } }, None)
+        .await?;
+    Ok(result.deleted_count)
+}
+
+impl AssetStore for MongoStore {
+    fn assets<'a>(&'a self) -> LocalBoxFuture<'a, StoreResult<Vec<models::Asset>>> {
+        Box::pin(async move {
+            let cursor = self.assets_collection.find(None, None).await?;
+            Ok(cursor.try_collect().await?)
+        })
+    }
+
+    fn find_asset<'a>(
+        &'a self,
+        address: &'a str,
+        chain: &'a str,
+        protocol: &'a str,
+    ) -> LocalBoxFuture<'a, StoreResult<Option<models::Asset>>> {
+        Box::pin(async move {
+            let filter = store::asset_filter(address, chain, protocol);
+            Ok(self.assets_collection.find_one(filter, None).await?)
+        })
+    }
+
+    fn upsert_asset<'a>(&'a self, asset: &'a models::Asset) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            let filter = store::asset_filter(&asset.address, &asset.chain, &asset.protocol);
+            let update = doc! { "$set": bson::to_document(asset)? };
+            upsert(&self.assets_collection, filter, update).await
+        })
+    }
+}
+
+impl FarmStore for MongoStore {
+    fn farms<'a>(&'a self) -> LocalBoxFuture<'a, StoreResult<Vec<models::Farm>>> {
+        Box::pin(async move {
+            let cursor = self.farms_collection.find(None, None).await?;
+            Ok(cursor.try_collect().await?)
+        })
+    }
+
+    fn upsert_farm<'a>(&'a self, farm: &'a models::Farm) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            let filter = store::farm_filter(farm);
+            let update = doc! { "$set": bson::to_document(farm)? };
+            upsert(&self.farms_collection, filter, update).await
+        })
+    }
+
+    fn update_farm_scores<'a>(
+        &'a self,
+        farm: &'a models::Farm,
+    ) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            self.farms_collection
+                .update_one(store::farm_filter(farm), store::score_update(farm)?, None)
+                .await?;
+            Ok(())
+        })
+    }
+}
+
+impl SnapshotStore for MongoStore {
+    fn insert_farm_snapshots<'a>(
+        &'a self,
+        snapshots: &'a [models::FarmSnapshot],
+    ) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(insert(&self.farm_snapshots_collection, snapshots))
+    }
+
+    fn insert_asset_snapshots<'a>(
+        &'a self,
+        snapshots: &'a [models::AssetSnapshot],
+    ) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(insert(&self.asset_snapshots_collection, snapshots))
+    }
+
+    fn farm_snapshots<'a>(
+        &'a self,
+        since: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<Vec<models::FarmSnapshot>>> {
+        Box::pin(taken_since(&self.farm_snapshots_collection, since))
+    }
+
+    fn asset_snapshots<'a>(
+        &'a self,
+        since: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<Vec<models::AssetSnapshot>>> {
+        Box::pin(taken_since(&self.asset_snapshots_collection, since))
+    }
+
+    fn prune_farm_snapshots<'a>(
+        &'a self,
+        before: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<u64>> {
+        Box::pin(prune(&self.farm_snapshots_collection, before))
+    }
+
+    fn prune_asset_snapshots<'a>(
+        &'a self,
+        before: bson::DateTime,
+    ) -> LocalBoxFuture<'a, StoreResult<u64>> {
+        Box::pin(prune(&self.asset_snapshots_collection, before))
+    }
+}
+
+impl TokenStore for MongoStore {
+    fn find_token<'a>(
+        &'a self,
+        address: &'a str,
+        chain: &'a str,
+    ) -> LocalBoxFuture<'a, StoreResult<Option<models::Token>>> {
+        Box::pin(async move {
+            let filter = store::token_filter(address, chain);
+            Ok(self.tokens_collection.find_one(filter, None).await?)
+        })
+    }
+
+    fn upsert_token<'a>(&'a self, token: &'a models::Token) -> LocalBoxFuture<'a, StoreResult<()>> {
+        Box::pin(async move {
+            let filter = store::token_filter(&token.address, &token.chain);
+            let update = doc! { "$set": bson::to_document(token)? };
+            upsert(&self.tokens_collection, filter, update).await
+        })
+    }
+}
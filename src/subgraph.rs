@@ -116,6 +116,25 @@ pub struct Block {
     pub timestamp: String,
 }
 
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairVolumesData {
+    pub pairs: Vec<PairVolume>,
+}
+
+/// A pair's cumulative volume, as of the block it was queried at.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairVolume {
+    pub id: String,
+    #[serde(rename = "reserveUSD")]
+    pub reserve_usd: String,
+    #[serde(rename = "volumeUSD")]
+    pub volume_usd: String,
+    #[serde(rename = "untrackedVolumeUSD")]
+    pub untracked_volume_usd: String,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PairsData {
@@ -0,0 +1,73 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use ethers::{prelude::Address, utils::to_checksum};
+
+use crate::chef::ChainClient;
+use crate::contracts;
+use crate::models;
+use crate::store;
+
+/// Resolves a token's name, symbol and decimals from its contract. Each token is read
+/// on-chain once, then served from the `tokens` collection and an in-process cache.
+pub struct TokenResolver {
+    clients: HashMap<String, ChainClient>,
+    store: Arc<dyn store::Store>,
+    cache: Mutex<HashMap<(String, String), models::Token>>,
+}
+
+impl TokenResolver {
+    pub fn new(clients: HashMap<String, ChainClient>, store: Arc<dyn store::Store>) -> Self {
+        TokenResolver {
+            clients,
+            store,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn resolve(
+        &self,
+        chain: &str,
+        address: &str,
+    ) -> Result<models::Token, Box<dyn std::error::Error>> {
+        let token_address = address.parse::<Address>()?;
+        let address = to_checksum(&token_address, None);
+        let key = (chain.to_string(), address.clone());
+
+        if let Some(token) = self.cache.lock().unwrap().get(&key) {
+            return Ok(token.clone());
+        }
+
+        let token = match self.store.find_token(&address, chain).await? {
+            Some(token) => token,
+            None => {
+                let client = self
+                    .clients
+                    .get(chain)
+                    .ok_or_else(|| format!("no rpc client for {}", chain))?;
+                let erc20 = contracts::IAnyswapV5ERC20::new(token_address, Arc::clone(client));
+                let token = models::Token {
+                    address: address.clone(),
+                    chain: chain.to_string(),
+                    name: erc20.name().call().await?,
+                    symbol: erc20.symbol().call().await?,
+                    decimals: erc20.decimals().call().await? as u32,
+                };
+                log::debug!(
+                    "resolved token {} on {}: {} ({} decimals)",
+                    token.address,
+                    token.chain,
+                    token.symbol,
+                    token.decimals
+                );
+                self.store.upsert_token(&token).await?;
+                token
+            }
+        };
+
+        self.cache.lock().unwrap().insert(key, token.clone());
+        Ok(token)
+    }
+}